@@ -0,0 +1,94 @@
+use std::any::Any;
+use std::sync::Arc;
+use hashbrown::HashMap;
+use crate::iface::type_token;
+use crate::introspection::{DependencyGraph, Lifetime, ServiceInfo};
+
+pub(crate) struct Factory<T>(pub(crate) Box<dyn Fn(&Container) -> T + Send + Sync>);
+
+#[derive(Default)]
+struct Inner {
+    deps: HashMap<String, Arc<dyn Any + Send + Sync>>,
+    info: HashMap<String, ServiceInfo>,
+}
+
+/// Thread-safe dependency map, cloning is cheap (one `Arc` increment)
+#[derive(Clone, Default)]
+pub struct Container {
+    inner: Arc<Inner>
+}
+
+impl Container {
+    pub fn new(deps: HashMap<String, Arc<dyn Any + Send + Sync>>) -> Self {
+        let info = deps
+            .keys()
+            .map(|k| (k.clone(), ServiceInfo::new(k, None, Lifetime::Singleton)))
+            .collect();
+        Self::with_info(deps, info)
+    }
+
+    pub(crate) fn with_info(deps: HashMap<String, Arc<dyn Any + Send + Sync>>, info: HashMap<String, ServiceInfo>) -> Self {
+        Self {
+            inner: Arc::new(Inner { deps, info })
+        }
+    }
+
+    pub fn get<T: Any>(&self, token: &str) -> Option<&T> {
+        self.inner.deps.get(token)?.downcast_ref::<T>()
+    }
+
+    pub fn get_arc<T: Any + Send + Sync>(&self, token: &str) -> Option<Arc<T>> {
+        self.inner.deps.get(token)?.clone().downcast::<T>().ok()
+    }
+
+    /// Resolves dependency registered with `register_as::<T>`
+    pub fn get_as<T: ?Sized + 'static>(&self) -> Option<&Arc<T>> {
+        self.get::<Arc<T>>(&type_token::<T>())
+    }
+
+    /// Creates a new instance of a transient dependency registered with `register_factory`
+    pub fn make<T: Any>(&self, token: &str) -> Option<T> {
+        self.get::<Factory<T>>(token).map(|f| (f.0)(self))
+    }
+
+    /// Same as [`Container::get`], but panics if dependency is missing or has another type
+    pub fn resolve<T: Any>(&self, token: &str) -> &T {
+        let Some(dep) = self.inner.deps.get(token) else {
+            let mut tokens: Vec<&str> = self.tokens().collect();
+            tokens.sort();
+            panic!("Missing dependency: {} (registered: {})", token, tokens.join(", "))
+        };
+        match dep.downcast_ref::<T>() {
+            Some(v) => v,
+            None => {
+                let registered = self.inner.info.get(token).and_then(|i| i.type_name).unwrap_or("?");
+                panic!("Invalid type for dependency: {} - must be {}, registered as {}", token, std::any::type_name::<T>(), registered)
+            }
+        }
+    }
+
+    pub fn contains(&self, token: &str) -> bool {
+        self.inner.deps.contains_key(token)
+    }
+
+    pub fn tokens(&self) -> impl Iterator<Item = &str> {
+        self.inner.deps.keys().map(String::as_str)
+    }
+
+    pub fn info(&self, token: &str) -> Option<&ServiceInfo> {
+        self.inner.info.get(token)
+    }
+
+    /// Registered services with their lifetime, type and declared dependencies
+    pub fn services(&self) -> DependencyGraph {
+        DependencyGraph::new(&self.inner.info)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.deps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.deps.is_empty()
+    }
+}
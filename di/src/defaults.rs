@@ -0,0 +1,34 @@
+use hashbrown::HashMap;
+
+pub const TRACING_TOKEN: &str = "tracing";
+pub const ENV_TOKEN: &str = "env";
+
+/// Snapshot of the process environment taken at registration time.
+/// `Debug` lists only the names, values may be secrets
+#[derive(Clone, Default)]
+pub struct EnvVars(HashMap<String, String>);
+
+impl std::fmt::Debug for EnvVars {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut names: Vec<&str> = self.0.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        f.debug_map().entries(names.into_iter().map(|k| (k, "***"))).finish()
+    }
+}
+
+impl EnvVars {
+    pub fn capture() -> Self {
+        Self(std::env::vars().collect())
+    }
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+}
+
+/// Handle to the tracing subscriber which is active while the container is built
+pub fn tracing_dispatch() -> tracing::Dispatch {
+    tracing::dispatcher::get_default(|d| d.clone())
+}
@@ -122,7 +122,13 @@ impl ProjectContainer for Container {
 
 #[cfg(test)]
 mod tests {
-    use crate::impls::DependencyBuilder;
+    use std::sync::Arc;
+    use crate::container::Container as SyncContainer;
+    use crate::defaults::{EnvVars, ENV_TOKEN, TRACING_TOKEN};
+    use crate::error::DiError;
+    use crate::iface::{type_token, DiModule};
+    use crate::introspection::Lifetime;
+    use crate::impls::{ConflictPolicy, DependencyBuilder, SyncDependencyBuilder};
     use super::*;
 
     #[test]
@@ -142,4 +148,192 @@ mod tests {
         assert_eq!("Some-service-value".to_string(), di.get_some_service().some_service_test());
         assert_eq!(3, di.deps.len())
     }
+
+    fn sync_container() -> SyncContainer {
+        let test_service = TestServiceStruct::new("Some-value-a".to_string());
+        let some_service = SomeServiceStruct::new("Some-service-value".to_string());
+        let mut builder = SyncDependencyBuilder::new();
+        builder.register_dep("test", Arc::new(Arc::new(test_service) as Arc<dyn TestService + Send + Sync>));
+        builder.register_dep("some-service", Arc::new(Arc::new(some_service) as Arc<dyn SomeService + Send + Sync>));
+        builder.build()
+    }
+
+    #[test]
+    fn test_sync_di() {
+        let di = sync_container();
+        assert_eq!("Some-value-a".to_string(), di.resolve::<Arc<dyn TestService + Send + Sync>>("test").some_told());
+        assert_eq!("Some-service-value".to_string(), di.resolve::<Arc<dyn SomeService + Send + Sync>>("some-service").some_service_test());
+        assert!(di.get::<Arc<dyn TestService + Send + Sync>>("some-service").is_none());
+        assert!(di.get::<String>("missing").is_none());
+        assert_eq!(2, di.len())
+    }
+
+    #[test]
+    fn test_sync_di_across_threads() {
+        let di = sync_container();
+        let handles = (0..4)
+            .map(|_| {
+                let di = di.clone();
+                std::thread::spawn(move || di.resolve::<Arc<dyn TestService + Send + Sync>>("test").some_told())
+            })
+            .collect::<Vec<_>>();
+        for h in handles {
+            assert_eq!("Some-value-a".to_string(), h.join().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_sync_di_register_default() {
+        unsafe { std::env::set_var("DI_TEST_DEFAULT_ENV", "value"); }
+        static CFG: &str = "static-config";
+        let mut builder = SyncDependencyBuilder::new();
+        builder.register_default().register_static("cfg", &CFG);
+        let di = builder.build();
+        let env = di.resolve::<EnvVars>(ENV_TOKEN);
+        assert_eq!(Some("value"), env.get("DI_TEST_DEFAULT_ENV"));
+        let debug = format!("{:?}", env);
+        assert!(debug.contains(r#""DI_TEST_DEFAULT_ENV": "***""#));
+        assert!(!debug.contains("value"));
+        assert!(di.get::<tracing::Dispatch>(TRACING_TOKEN).is_some());
+        assert_eq!("static-config", **di.resolve::<&'static &str>("cfg"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Missing dependency: missing")]
+    fn test_sync_di_missing() {
+        let di = sync_container();
+        di.resolve::<String>("missing");
+    }
+
+    struct ServicesModule;
+
+    impl DiModule<SyncDependencyBuilder> for ServicesModule {
+        fn register(&self, b: &mut SyncDependencyBuilder) {
+            b.register_as::<dyn TestService + Send + Sync>(Arc::new(TestServiceStruct::new("module-a".to_string())));
+            b.register_as::<dyn SomeService + Send + Sync>(Arc::new(SomeServiceStruct::new("module-some".to_string())));
+        }
+    }
+
+    struct DualModule;
+
+    impl DiModule<SyncDependencyBuilder> for DualModule {
+        fn register(&self, b: &mut SyncDependencyBuilder) {
+            b.register_as::<dyn TestService + Send + Sync>(Arc::new(DualServiceTestStruct::new("module-b".to_string())));
+        }
+    }
+
+    struct BoxedModule;
+
+    impl DiModule for BoxedModule {
+        fn register(&self, b: &mut DependencyBuilder) {
+            b.register_dep("test", Box::new(Box::new(TestServiceStruct::new("boxed".to_string())) as Box<dyn TestService>));
+        }
+    }
+
+    #[test]
+    fn test_di_modules() {
+        let mut builder = SyncDependencyBuilder::new();
+        builder.install(ServicesModule).install(DualModule);
+        let di = builder.build();
+        assert_eq!("module-b".to_string(), di.get_as::<dyn TestService + Send + Sync>().unwrap().some_told());
+        assert_eq!("module-some".to_string(), di.get_as::<dyn SomeService + Send + Sync>().unwrap().some_service_test());
+
+        let mut builder = DependencyBuilder::new();
+        builder.install(BoxedModule);
+        let deps = builder.build();
+        assert_eq!("boxed".to_string(), deps["test"].downcast_ref::<Box<dyn TestService>>().unwrap().some_told());
+    }
+
+    #[test]
+    fn test_di_override() {
+        let mut builder = SyncDependencyBuilder::new();
+        builder
+            .set_conflict_policy(ConflictPolicy::Error)
+            .override_as::<dyn TestService + Send + Sync>(Arc::new(DualServiceTestStruct::new("fake".to_string())))
+            .install(ServicesModule);
+        let di = builder.try_build().unwrap();
+        assert_eq!("fake".to_string(), di.get_as::<dyn TestService + Send + Sync>().unwrap().some_told());
+        assert_eq!("module-some".to_string(), di.get_as::<dyn SomeService + Send + Sync>().unwrap().some_service_test());
+    }
+
+    #[test]
+    fn test_di_conflict_policy() {
+        let mut builder = SyncDependencyBuilder::new();
+        builder
+            .set_conflict_policy(ConflictPolicy::Error)
+            .install(ServicesModule)
+            .install(DualModule);
+        let Err(err) = builder.try_build() else { panic!("Conflict must be reported") };
+        assert_eq!(DiError::Conflict(vec![type_token::<dyn TestService + Send + Sync>()]), err);
+    }
+
+    #[test]
+    fn test_di_conflict_reported_once() {
+        let mut builder = DependencyBuilder::new();
+        builder
+            .set_conflict_policy(ConflictPolicy::Error)
+            .install(BoxedModule)
+            .install(BoxedModule)
+            .install(BoxedModule);
+        assert_eq!(Some(DiError::Conflict(vec!["test".to_string()])), builder.try_build().err());
+    }
+
+    #[test]
+    fn test_type_token() {
+        let token = type_token::<dyn TestService + Send + Sync>();
+        assert!(token.starts_with(std::any::type_name::<dyn TestService + Send + Sync>()));
+        assert_ne!(token, type_token::<dyn TestService>());
+        assert_eq!(token, type_token::<dyn TestService + Send + Sync>());
+    }
+
+    #[test]
+    #[should_panic(expected = "Dependency registered more than once: test")]
+    fn test_di_conflict_build_panics() {
+        let mut builder = DependencyBuilder::new();
+        builder
+            .set_conflict_policy(ConflictPolicy::Error)
+            .install(BoxedModule)
+            .install(BoxedModule);
+        builder.build();
+    }
+
+    #[test]
+    fn test_di_introspection() {
+        let mut builder = SyncDependencyBuilder::new();
+        builder
+            .register("name", "service-name".to_string())
+            .register_dep("erased", Arc::new(1u8))
+            .register_factory("test", &["name"], |c| {
+                Arc::new(TestServiceStruct::new(c.resolve::<String>("name").clone())) as Arc<dyn TestService + Send + Sync>
+            })
+            .depends_on("erased", &["mailer"]);
+        let di = builder.build();
+
+        assert_eq!("service-name".to_string(), di.make::<Arc<dyn TestService + Send + Sync>>("test").unwrap().some_told());
+        let test = di.info("test").unwrap();
+        assert_eq!(Lifetime::Transient, test.lifetime);
+        assert_eq!(vec!["name".to_string()], test.dependencies);
+        assert_eq!(Some(std::any::type_name::<String>()), di.info("name").unwrap().type_name);
+        assert_eq!(None, di.info("erased").unwrap().type_name);
+
+        let graph = di.services();
+        assert_eq!(vec![("erased", "mailer")], graph.missing());
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph dependencies {"));
+        assert!(dot.contains("\"test\" -> \"name\";"));
+        assert!(dot.contains("\"mailer\" [label=\"mailer (missing)\", style=dashed];"));
+        let mermaid = graph.to_mermaid();
+        assert!(mermaid.starts_with("graph TD"));
+        assert!(mermaid.contains("n2 --> n1"));
+        assert!(mermaid.contains("n3[\"mailer (missing)\"]"));
+        assert!(graph.to_string().contains("test: alloc::sync::Arc<dyn di::di_tests::TestService + core::marker::Send + core::marker::Sync> (transient) -> name"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Missing dependency: missing (registered: name)")]
+    fn test_di_missing_lists_registered() {
+        let mut builder = SyncDependencyBuilder::new();
+        builder.register("name", "service-name".to_string());
+        builder.build().resolve::<String>("missing");
+    }
 }
@@ -0,0 +1,16 @@
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DiError {
+    Conflict(Vec<String>),
+}
+
+impl Display for DiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiError::Conflict(tokens) => write!(f, "Dependency registered more than once: {}", tokens.join(", ")),
+        }
+    }
+}
+
+impl std::error::Error for DiError {}
@@ -1,8 +1,32 @@
-use std::any::Any;
-use hashbrown::HashMap;
+use crate::error::DiError;
+use crate::impls::DependencyBuilder;
+use crate::introspection::DependencyGraph;
 
 pub trait IDependencyBuilder {
-    fn register_dep(&mut self,token: &str, dep: Box<dyn Any>) -> &mut Self;
+    type Dep;
+    type Output;
+    fn register_dep(&mut self,token: &str, dep: Self::Dep) -> &mut Self;
+    /// Replaces dependency regardless of conflict policy, later regular registrations of that token are ignored
+    fn override_dep(&mut self,token: &str, dep: Self::Dep) -> &mut Self;
+    /// Declares tokens the registered dependency needs, used only for introspection
+    fn depends_on(&mut self, token: &str, deps: &[&str]) -> &mut Self;
+    fn services(&self) -> DependencyGraph;
     fn register_default(&mut self) -> &mut Self;
-    fn build(self) -> HashMap<String, Box<dyn Any>>;
-}
\ No newline at end of file
+    fn try_build(self) -> Result<Self::Output, DiError>;
+    fn build(self) -> Self::Output;
+    fn install<M: DiModule<Self>>(&mut self, module: M) -> &mut Self where Self: Sized {
+        module.register(self);
+        self
+    }
+}
+
+/// Group of registrations which can be installed into a builder at once
+pub trait DiModule<B = DependencyBuilder> {
+    fn register(&self, b: &mut B);
+}
+
+/// Token used by typed registrations (`register_as`, `override_as`).
+/// Unique per `TypeId`, the type name in front of it is there only to be readable in graphs and errors
+pub fn type_token<T: ?Sized + 'static>() -> String {
+    format!("{}@{:?}", std::any::type_name::<T>(), std::any::TypeId::of::<T>())
+}
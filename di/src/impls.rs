@@ -1,38 +1,291 @@
 use std::any::Any;
-use hashbrown::HashMap;
-use crate::iface::{IDependencyBuilder};
+use std::sync::Arc;
+use hashbrown::{HashMap, HashSet};
+use crate::container::{Container, Factory};
+use crate::defaults::{tracing_dispatch, EnvVars, ENV_TOKEN, TRACING_TOKEN};
+use crate::error::DiError;
+use crate::iface::{type_token, DiModule, IDependencyBuilder};
+use crate::introspection::{DependencyGraph, Lifetime, ServiceInfo};
+
+/// What happens when the same token is registered twice
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Later registration replaces the earlier one
+    #[default]
+    LastWins,
+    /// Earlier registration is kept and `build` fails
+    Error,
+}
+
+struct Registry<D> {
+    deps: HashMap<String, D>,
+    info: HashMap<String, ServiceInfo>,
+    overridden: HashSet<String>,
+    conflicts: Vec<String>,
+    policy: ConflictPolicy,
+}
+
+impl<D> Default for Registry<D> {
+    fn default() -> Self {
+        Self {
+            deps: HashMap::new(),
+            info: HashMap::new(),
+            overridden: HashSet::new(),
+            conflicts: Vec::new(),
+            policy: ConflictPolicy::default(),
+        }
+    }
+}
+
+impl<D> Registry<D> {
+    fn insert(&mut self, dep: D, info: ServiceInfo) {
+        let token = info.token.as_str();
+        if self.overridden.contains(token) {
+            tracing::debug!("Dependency {} is overridden, registration ignored", token);
+            return;
+        }
+        if self.deps.contains_key(token) {
+            match self.policy {
+                ConflictPolicy::LastWins => tracing::debug!("Dependency {} registered again, replacing", token),
+                ConflictPolicy::Error => {
+                    if !self.conflicts.iter().any(|c| c == token) {
+                        self.conflicts.push(token.to_string());
+                    }
+                    return;
+                }
+            }
+        }
+        self.deps.insert(token.to_string(), dep);
+        self.info.insert(token.to_string(), info);
+    }
+
+    fn force_insert(&mut self, dep: D, info: ServiceInfo) {
+        self.overridden.insert(info.token.clone());
+        self.deps.insert(info.token.clone(), dep);
+        self.info.insert(info.token.clone(), info);
+    }
+
+    fn depends_on(&mut self, token: &str, deps: &[&str]) {
+        let Some(info) = self.info.get_mut(token) else {
+            tracing::warn!("Dependencies declared for unknown token {}", token);
+            return;
+        };
+        for dep in deps {
+            if !info.dependencies.iter().any(|d| d == dep) {
+                info.dependencies.push(dep.to_string());
+            }
+        }
+    }
+
+    fn finish(self) -> Result<Self, DiError> {
+        if !self.conflicts.is_empty() {
+            return Err(DiError::Conflict(self.conflicts));
+        }
+        Ok(self)
+    }
+}
 
 pub struct DependencyBuilder {
-    deps: HashMap<String, Box<dyn Any>>
+    deps: Registry<Box<dyn Any>>
+}
+
+impl Default for DependencyBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl DependencyBuilder {
     pub fn new() -> Self {
         Self {
-            deps: HashMap::new()
+            deps: Registry::default()
         }
     }
+    pub fn set_conflict_policy(&mut self, policy: ConflictPolicy) -> &mut Self {
+        self.deps.policy = policy;
+        self
+    }
     pub fn register_default(&mut self) -> &mut Self {
         IDependencyBuilder::register_default(self)
     }
     pub fn register_dep(&mut self,token: &str, dep: Box<dyn Any>) -> &mut Self {
         IDependencyBuilder::register_dep(self, token, dep)
     }
+    pub fn override_dep(&mut self,token: &str, dep: Box<dyn Any>) -> &mut Self {
+        IDependencyBuilder::override_dep(self, token, dep)
+    }
+    /// Same as `register_dep`, but keeps the type name for introspection
+    pub fn register<T: Any>(&mut self, token: &str, dep: T) -> &mut Self {
+        self.deps.insert(Box::new(dep), ServiceInfo::new(token, Some(std::any::type_name::<T>()), Lifetime::Singleton));
+        self
+    }
+    /// Registers `dep` under [`type_token::<T>`], e.g. `register_as::<dyn Mailer>(Box::new(SmtpMailer))`
+    pub fn register_as<T: ?Sized + 'static>(&mut self, dep: Box<T>) -> &mut Self {
+        let token = type_token::<T>();
+        self.deps.insert(Box::new(dep), ServiceInfo::new(&token, Some(std::any::type_name::<T>()), Lifetime::Singleton));
+        self
+    }
+    pub fn override_as<T: ?Sized + 'static>(&mut self, dep: Box<T>) -> &mut Self {
+        let token = type_token::<T>();
+        self.deps.force_insert(Box::new(dep), ServiceInfo::new(&token, Some(std::any::type_name::<T>()), Lifetime::Singleton));
+        self
+    }
+    pub fn depends_on(&mut self, token: &str, deps: &[&str]) -> &mut Self {
+        IDependencyBuilder::depends_on(self, token, deps)
+    }
+    pub fn services(&self) -> DependencyGraph {
+        IDependencyBuilder::services(self)
+    }
+    pub fn install<M: DiModule<Self>>(&mut self, module: M) -> &mut Self {
+        IDependencyBuilder::install(self, module)
+    }
+    pub fn try_build(self) -> Result<HashMap<String, Box<dyn Any>>, DiError> {
+        IDependencyBuilder::try_build(self)
+    }
     pub fn build(self) -> HashMap<String, Box<dyn Any>> {
         IDependencyBuilder::build(self)
     }
 }
 
 impl IDependencyBuilder for DependencyBuilder {
+    type Dep = Box<dyn Any>;
+    type Output = HashMap<String, Box<dyn Any>>;
     fn register_dep(&mut self,token: &str, dep: Box<dyn Any>) -> &mut Self {
-        self.deps.insert(token.to_string(), dep);
-        return self
+        self.deps.insert(dep, ServiceInfo::new(token, None, Lifetime::Singleton));
+        self
+    }
+    fn override_dep(&mut self,token: &str, dep: Box<dyn Any>) -> &mut Self {
+        self.deps.force_insert(dep, ServiceInfo::new(token, None, Lifetime::Singleton));
+        self
+    }
+    fn depends_on(&mut self, token: &str, deps: &[&str]) -> &mut Self {
+        self.deps.depends_on(token, deps);
+        self
+    }
+    fn services(&self) -> DependencyGraph {
+        DependencyGraph::new(&self.deps.info)
     }
     fn register_default(&mut self) -> &mut Self {
-        //todo self.registerDep("some name",Box::new(some lib::new()))
-        return self
+        self.register(TRACING_TOKEN, tracing_dispatch());
+        self.register(ENV_TOKEN, EnvVars::capture());
+        self
+    }
+    fn try_build(self) -> Result<HashMap<String, Box<dyn Any>>, DiError> {
+        Ok(self.deps.finish()?.deps)
     }
     fn build(self) -> HashMap<String, Box<dyn Any>> {
-        self.deps
+        self.try_build().unwrap_or_else(|e| panic!("{}", e))
     }
-}
\ No newline at end of file
+}
+
+/// Same as [`DependencyBuilder`], but produces a `Send + Sync` [`Container`]
+/// which can be shared between tokio tasks or put into axum `State`
+pub struct SyncDependencyBuilder {
+    deps: Registry<Arc<dyn Any + Send + Sync>>
+}
+
+impl Default for SyncDependencyBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SyncDependencyBuilder {
+    pub fn new() -> Self {
+        Self {
+            deps: Registry::default()
+        }
+    }
+    pub fn set_conflict_policy(&mut self, policy: ConflictPolicy) -> &mut Self {
+        self.deps.policy = policy;
+        self
+    }
+    pub fn register_default(&mut self) -> &mut Self {
+        IDependencyBuilder::register_default(self)
+    }
+    pub fn register_dep(&mut self,token: &str, dep: Arc<dyn Any + Send + Sync>) -> &mut Self {
+        IDependencyBuilder::register_dep(self, token, dep)
+    }
+    pub fn override_dep(&mut self,token: &str, dep: Arc<dyn Any + Send + Sync>) -> &mut Self {
+        IDependencyBuilder::override_dep(self, token, dep)
+    }
+    /// Same as `register_dep`, but keeps the type name for introspection
+    pub fn register<T: Any + Send + Sync>(&mut self, token: &str, dep: T) -> &mut Self {
+        self.deps.insert(Arc::new(dep), ServiceInfo::new(token, Some(std::any::type_name::<T>()), Lifetime::Singleton));
+        self
+    }
+    /// Registers `dep` under [`type_token::<T>`], resolve it with [`Container::get_as`]
+    pub fn register_as<T: ?Sized + Send + Sync + 'static>(&mut self, dep: Arc<T>) -> &mut Self {
+        let token = type_token::<T>();
+        self.deps.insert(Arc::new(dep), ServiceInfo::new(&token, Some(std::any::type_name::<T>()), Lifetime::Singleton));
+        self
+    }
+    pub fn override_as<T: ?Sized + Send + Sync + 'static>(&mut self, dep: Arc<T>) -> &mut Self {
+        let token = type_token::<T>();
+        self.deps.force_insert(Arc::new(dep), ServiceInfo::new(&token, Some(std::any::type_name::<T>()), Lifetime::Singleton));
+        self
+    }
+    /// Registers a global like `Env::fetch()` generated by `utils::env_config!`
+    pub fn register_static<T: Any + Send + Sync>(&mut self, token: &str, dep: &'static T) -> &mut Self {
+        self.register(token, dep)
+    }
+    /// Registers a transient service, every [`Container::make`] calls `factory` again.
+    /// `deps` are tokens the factory resolves from the container
+    pub fn register_factory<T, F>(&mut self, token: &str, deps: &[&str], factory: F) -> &mut Self
+    where
+        T: Any,
+        F: Fn(&Container) -> T + Send + Sync + 'static,
+    {
+        let info = ServiceInfo::new(token, Some(std::any::type_name::<T>()), Lifetime::Transient);
+        self.deps.insert(Arc::new(Factory(Box::new(factory))), info);
+        self.deps.depends_on(token, deps);
+        self
+    }
+    pub fn depends_on(&mut self, token: &str, deps: &[&str]) -> &mut Self {
+        IDependencyBuilder::depends_on(self, token, deps)
+    }
+    pub fn services(&self) -> DependencyGraph {
+        IDependencyBuilder::services(self)
+    }
+    pub fn install<M: DiModule<Self>>(&mut self, module: M) -> &mut Self {
+        IDependencyBuilder::install(self, module)
+    }
+    pub fn try_build(self) -> Result<Container, DiError> {
+        IDependencyBuilder::try_build(self)
+    }
+    pub fn build(self) -> Container {
+        IDependencyBuilder::build(self)
+    }
+}
+
+impl IDependencyBuilder for SyncDependencyBuilder {
+    type Dep = Arc<dyn Any + Send + Sync>;
+    type Output = Container;
+    fn register_dep(&mut self,token: &str, dep: Arc<dyn Any + Send + Sync>) -> &mut Self {
+        self.deps.insert(dep, ServiceInfo::new(token, None, Lifetime::Singleton));
+        self
+    }
+    fn override_dep(&mut self,token: &str, dep: Arc<dyn Any + Send + Sync>) -> &mut Self {
+        self.deps.force_insert(dep, ServiceInfo::new(token, None, Lifetime::Singleton));
+        self
+    }
+    fn depends_on(&mut self, token: &str, deps: &[&str]) -> &mut Self {
+        self.deps.depends_on(token, deps);
+        self
+    }
+    fn services(&self) -> DependencyGraph {
+        DependencyGraph::new(&self.deps.info)
+    }
+    fn register_default(&mut self) -> &mut Self {
+        self.register(TRACING_TOKEN, tracing_dispatch());
+        self.register(ENV_TOKEN, EnvVars::capture());
+        self
+    }
+    fn try_build(self) -> Result<Container, DiError> {
+        let registry = self.deps.finish()?;
+        Ok(Container::with_info(registry.deps, registry.info))
+    }
+    fn build(self) -> Container {
+        self.try_build().unwrap_or_else(|e| panic!("{}", e))
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt::{Display, Write};
+use hashbrown::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lifetime {
+    /// One instance shared by every consumer
+    Singleton,
+    /// New instance on every `Container::make`
+    Transient,
+}
+
+impl Display for Lifetime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Lifetime::Singleton => write!(f, "singleton"),
+            Lifetime::Transient => write!(f, "transient"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServiceInfo {
+    pub token: String,
+    /// `None` for type-erased registrations made with `register_dep`
+    pub type_name: Option<&'static str>,
+    pub lifetime: Lifetime,
+    pub dependencies: Vec<String>,
+}
+
+impl ServiceInfo {
+    pub fn new(token: &str, type_name: Option<&'static str>, lifetime: Lifetime) -> Self {
+        Self {
+            token: token.to_string(),
+            type_name,
+            lifetime,
+            dependencies: vec![],
+        }
+    }
+}
+
+/// Snapshot of registrations which can be rendered as DOT or Mermaid text
+#[derive(Clone, Debug, Default)]
+pub struct DependencyGraph {
+    services: Vec<ServiceInfo>,
+}
+
+impl DependencyGraph {
+    pub fn new(info: &HashMap<String, ServiceInfo>) -> Self {
+        let mut services: Vec<ServiceInfo> = info.values().cloned().collect();
+        services.sort_by(|a, b| a.token.cmp(&b.token));
+        Self { services }
+    }
+
+    pub fn services(&self) -> &[ServiceInfo] {
+        &self.services
+    }
+
+    /// Declared dependencies which are not registered, as `(service, dependency)`
+    pub fn missing(&self) -> Vec<(&str, &str)> {
+        self.services
+            .iter()
+            .flat_map(|s| s.dependencies.iter().map(move |d| (s.token.as_str(), d.as_str())))
+            .filter(|(_, d)| !self.services.iter().any(|s| s.token == *d))
+            .collect()
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph dependencies {\n");
+        for s in self.services.iter() {
+            let _ = writeln!(out, "    \"{}\" [label=\"{}\"];", escape_dot(&s.token), escape_dot(&label(s)));
+        }
+        let mut missing: Vec<&str> = self.missing().into_iter().map(|(_, d)| d).collect();
+        missing.sort();
+        missing.dedup();
+        for dep in missing {
+            let _ = writeln!(out, "    \"{}\" [label=\"{} (missing)\", style=dashed];", escape_dot(dep), escape_dot(dep));
+        }
+        for s in self.services.iter() {
+            for d in s.dependencies.iter() {
+                let _ = writeln!(out, "    \"{}\" -> \"{}\";", escape_dot(&s.token), escape_dot(d));
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        let mut ids: HashMap<&str, usize> = HashMap::new();
+        let mut out = String::from("graph TD\n");
+        for s in self.services.iter() {
+            let id = ids.len();
+            ids.insert(&s.token, id);
+            let _ = writeln!(out, "    n{}[\"{}\"]", id, escape_mermaid(&label(s)));
+        }
+        for (_, dep) in self.missing() {
+            if ids.contains_key(dep) {
+                continue;
+            }
+            let id = ids.len();
+            ids.insert(dep, id);
+            let _ = writeln!(out, "    n{}[\"{} (missing)\"]", id, escape_mermaid(dep));
+        }
+        for s in self.services.iter() {
+            for d in s.dependencies.iter() {
+                let _ = writeln!(out, "    n{} --> n{}", ids[s.token.as_str()], ids[d.as_str()]);
+            }
+        }
+        out
+    }
+}
+
+impl Display for DependencyGraph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for s in self.services.iter() {
+            write!(f, "{}: {} ({})", s.token, s.type_name.unwrap_or("?"), s.lifetime)?;
+            if !s.dependencies.is_empty() {
+                write!(f, " -> {}", s.dependencies.join(", "))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+fn label(s: &ServiceInfo) -> String {
+    format!("{}\n{}\n{}", s.token, s.type_name.unwrap_or("?"), s.lifetime)
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn escape_mermaid(s: &str) -> String {
+    s.replace('"', "#quot;").replace('<', "#lt;").replace('>', "#gt;").replace('\n', "<br/>")
+}
@@ -1,3 +1,8 @@
 pub mod iface;
 pub mod impls;
-mod di_tests;
\ No newline at end of file
+pub mod container;
+pub mod defaults;
+pub mod error;
+pub mod introspection;
+#[allow(clippy::borrowed_box, clippy::collapsible_if)]
+mod di_tests;
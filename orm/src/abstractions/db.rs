@@ -1,3 +1,19 @@
-use crate::prelude::{SqlGen};
+use futures_core::future::BoxFuture;
 
-pub trait OrmDB : sqlx::Database + SqlGen {}
\ No newline at end of file
+use crate::prelude::{IsolationLevel, SqlGen};
+
+pub trait OrmDB : sqlx::Database + SqlGen {
+    /// Error after which the whole transaction may succeed if run again: serialization failures and deadlocks
+    fn is_retryable(err: &dyn sqlx::error::DatabaseError) -> bool {
+        matches!(err.code().as_deref(), Some("40001" | "40P01"))
+    }
+    /// Transaction on a connection of the pool started by `begin_statement`
+    fn begin_isolated(pool: &sqlx::Pool<Self>, isolation: IsolationLevel) -> BoxFuture<'_, Result<sqlx::Transaction<'static, Self>, sqlx::Error>> {
+        Box::pin(async move {
+            match Self::begin_statement(isolation) {
+                Some(statement) => pool.begin_with(statement).await,
+                None => pool.begin().await,
+            }
+        })
+    }
+}
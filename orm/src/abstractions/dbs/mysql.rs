@@ -1,7 +1,42 @@
 impl crate::prelude::SqlGen for sqlx::MySql {
     fn placeholder(_i: usize) -> String {
-        return "?".to_owned()
+        "?".to_owned()
+    }
+    fn quote_ident(ident: &str) -> String {
+        format!("`{}`", ident.replace('`', "``"))
+    }
+    fn supports_returning() -> bool {
+        false
+    }
+    fn last_insert_id() -> Option<&'static str> {
+        Some("LAST_INSERT_ID()")
+    }
+    fn upsert_clause(_pk: &str, columns: &[&str]) -> String {
+        let updates: Vec<String> = columns.iter().map(|c| format!("{0} = VALUES({0})", Self::quote_ident(c))).collect();
+        format!("ON DUPLICATE KEY UPDATE {}", updates.join(", "))
+    }
+    /// The level is set by `isolation_statement`
+    fn begin_statement(_isolation: crate::prelude::IsolationLevel) -> Option<String> {
+        None
+    }
+    /// MySQL sets the level of the next transaction before it is started
+    fn isolation_statement(isolation: crate::prelude::IsolationLevel) -> Option<String> {
+        Some(format!("SET TRANSACTION ISOLATION LEVEL {}", isolation.as_sql()))
     }
 }
 
-impl crate::prelude::OrmDB for sqlx::MySql {}
\ No newline at end of file
+impl crate::prelude::OrmDB for sqlx::MySql {
+    /// Runs `isolation_statement` and then starts the transaction on the same connection
+    fn begin_isolated(
+        pool: &sqlx::Pool<Self>,
+        isolation: crate::prelude::IsolationLevel,
+    ) -> futures_core::future::BoxFuture<'_, Result<sqlx::Transaction<'static, Self>, sqlx::Error>> {
+        Box::pin(async move {
+            let mut conn = pool.acquire().await?;
+            if let Some(statement) = <Self as crate::prelude::SqlGen>::isolation_statement(isolation) {
+                sqlx::Executor::execute(&mut *conn, statement.as_str()).await?;
+            }
+            sqlx::Transaction::begin(conn, None).await
+        })
+    }
+}
\ No newline at end of file
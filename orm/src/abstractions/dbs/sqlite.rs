@@ -1,7 +1,28 @@
 impl crate::prelude::SqlGen for sqlx::Sqlite {
     fn placeholder(_i: usize) -> String {
-        return "?".to_owned()
+        "?".to_owned()
+    }
+    /// Schemas are attached databases in SQLite, `main` and the Postgres default `public` mean the main one
+    fn full_table_name(schema: &str, table: &str) -> String {
+        match schema {
+            "" | "main" | "public" => Self::quote_ident(table),
+            _ => format!("{}.{}", Self::quote_ident(schema), Self::quote_ident(table)),
+        }
+    }
+    fn last_insert_id() -> Option<&'static str> {
+        Some("last_insert_rowid()")
+    }
+    /// Transactions in SQLite are always serializable
+    fn begin_statement(_isolation: crate::prelude::IsolationLevel) -> Option<String> {
+        None
     }
 }
 
-impl crate::prelude::OrmDB for sqlx::Postgres {}
+impl crate::prelude::OrmDB for sqlx::Sqlite {
+    /// `SQLITE_BUSY` and `SQLITE_LOCKED` with their extended codes
+    fn is_retryable(err: &dyn sqlx::error::DatabaseError) -> bool {
+        err.code()
+            .and_then(|c| c.parse::<i32>().ok())
+            .is_some_and(|c| matches!(c & 0xff, 5 | 6))
+    }
+}
\ No newline at end of file
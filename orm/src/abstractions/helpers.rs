@@ -29,6 +29,26 @@ impl<T> Optional<T> {
 
 pub use Optional::*;
 
+/// Version column value of an inserted row when the model did not set it
+pub const INITIAL_VERSION: u8 = 1;
+
+/// `NotSet` is meant to be skipped with `skip_serializing_if = "Optional::is_none"`, otherwise it is written as `null`
+impl<T: serde::Serialize> serde::Serialize for Optional<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Optional::Set(v) => v.serialize(serializer),
+            Optional::NotSet => serializer.serialize_none(),
+        }
+    }
+}
+
+/// With `#[serde(default)]` an absent key stays `NotSet`, `null` for `Optional<Option<T>>` becomes `Set(None)`
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Optional<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Optional::Set)
+    }
+}
+
 impl<'r, DB, T> Decode<'r, DB> for Optional<T>
 where
     DB: Database,
@@ -57,17 +77,66 @@ impl<T: Type<DB>, DB: Database> Type<DB> for Optional<T> {
 
 pub trait SqlGen {
     fn placeholder(i: usize) -> String;
+    /// Table or column name safe to use even if it is a reserved word
+    fn quote_ident(ident: &str) -> String {
+        format!(r#""{}""#, ident.replace('"', r#""""#))
+    }
     fn full_table_name(schema: &str, table: &str) -> String {
-        format!(r#""{}"."{}""#, schema, table)
+        format!("{}.{}", Self::quote_ident(schema), Self::quote_ident(table))
+    }
+    /// `RETURNING *` after inserts, updates and deletes, without it rows are selected by primary key afterwards
+    fn supports_returning() -> bool {
+        true
+    }
+    /// Key generated by the last insert on the connection, used when the primary key was not set
+    fn last_insert_id() -> Option<&'static str> {
+        None
+    }
+    /// Tail of an upsert after `VALUES (..)`, updating `columns` on a primary key conflict
+    fn upsert_clause(pk: &str, columns: &[&str]) -> String {
+        let updates: Vec<String> = columns.iter().map(|c| format!("{0} = EXCLUDED.{0}", Self::quote_ident(c))).collect();
+        format!("ON CONFLICT ({}) DO UPDATE SET {}", Self::quote_ident(pk), updates.join(", "))
+    }
+    /// Statement starting a transaction with the isolation level, `None` to start it as usual
+    fn begin_statement(isolation: IsolationLevel) -> Option<String> {
+        Some(format!("BEGIN ISOLATION LEVEL {}", isolation.as_sql()))
+    }
+    /// Statement run on its own before the transaction is started, for databases setting the level of the next transaction
+    fn isolation_statement(_isolation: IsolationLevel) -> Option<String> {
+        None
     }
 }
 
+fn returning<DB: SqlGen>() -> &'static str {
+    if DB::supports_returning() { " RETURNING *" } else { "" }
+}
+
 pub trait SqlBuilder<DB: OrmDB> {
-    fn select_by_pk() -> String;
+    fn select_by_pk() -> String {
+        Self::select_by_pk_scoped(DeletedScope::Alive)
+    }
+    fn select_by_pk_scoped(scope: DeletedScope) -> String;
     fn delete_by_pk() -> String;
-    fn count() -> String;
+    fn force_delete_by_pk() -> String;
+    fn restore_by_pk() -> Result<String, OrmError>;
+    fn count() -> String {
+        Self::count_scoped(DeletedScope::Alive)
+    }
+    fn count_scoped(scope: DeletedScope) -> String;
+    /// Condition selecting the rows visible in the scope, `None` if every row is
+    fn scope_condition(scope: DeletedScope) -> Option<String>;
+    /// Row just inserted without a primary key, for dialects without `RETURNING`
+    fn select_last_inserted() -> Result<String, OrmError>;
     fn insert_for(&self) -> Result<String, OrmError>;
-    fn update_for(&self) -> Result<String, OrmError>;
+    fn update_for(&self) -> Result<String, OrmError> {
+        self.update_with_bind_order().map(|(sql, _)| sql)
+    }
+    /// Columns bound to `update_for` in placeholder order: changed ones, the primary key and the version
+    fn update_bind_order(&self) -> Result<Vec<&'static str>, OrmError> {
+        self.update_with_bind_order().map(|(_, order)| order)
+    }
+    /// `update_for` and `update_bind_order` from a single pass over the columns
+    fn update_with_bind_order(&self) -> Result<(String, Vec<&'static str>), OrmError>;
     fn upsert_for(&self) -> Result<String, OrmError>;
 }
 
@@ -77,6 +146,12 @@ pub enum OrmError {
     NothingToUpdate,
     MissingPrimaryKey,
     NothingToInsert,
+    /// Row was changed by someone else since it was loaded
+    StaleObject,
+    /// Restore of a table without a soft delete column
+    NotSoftDeletable,
+    /// Full `select` or `delete` statement on a soft delete table, which can't be scoped
+    UnscopedStatement(&'static str),
 }
 
 impl Display for OrmError {
@@ -86,6 +161,10 @@ impl Display for OrmError {
             OrmError::NothingToUpdate => write!(f, "Nothing to update"),
             OrmError::MissingPrimaryKey => write!(f, "Missing primary key"),
             OrmError::NothingToInsert => write!(f, "Nothing to insert"),
+            OrmError::StaleObject => write!(f, "Stale object: row was modified concurrently"),
+            OrmError::NotSoftDeletable => write!(f, "Table has no soft delete column"),
+            OrmError::UnscopedStatement("delete") => write!(f, "Full delete statement would remove soft deleted rows, use force_delete or start the query with where"),
+            OrmError::UnscopedStatement(prefix) => write!(f, "Full {} statement would see soft deleted rows, use with_deleted or omit the {} prefix", prefix, prefix),
         }
     }
 }
@@ -96,6 +175,42 @@ impl std::error::Error for OrmError {
     }
 }
 
+/// Changed columns, the primary key and the version column of an update
+fn update_columns<T: TableSelector + ?Sized>(this: &T) -> Result<(Vec<&'static str>, &'static str, Option<&'static str>), OrmError> {
+    let mut set_cols = Vec::new();
+    let mut pk_col = None;
+    let mut version = None;
+    for col in T::columns().iter() {
+        if col.is_primary {
+            pk_col = Some(col.name);
+            continue;
+        }
+        if col.is_version {
+            if !this.is_field_set(col.name) {
+                return Err(OrmError::MissingValue(col.name));
+            }
+            version = Some(col.name);
+            continue;
+        }
+        if col.auto_create || col.auto_update {
+            continue;
+        }
+        if this.is_field_dirty(col.name) {
+            set_cols.push(col.name);
+        }
+    }
+
+    if set_cols.is_empty() {
+        return Err(OrmError::NothingToUpdate);
+    }
+    let pk_col = pk_col
+        .ok_or(OrmError::MissingPrimaryKey)?;
+    if !this.is_field_set(pk_col) {
+        return Err(OrmError::MissingValue(pk_col));
+    }
+    Ok((set_cols, pk_col, version))
+}
+
 impl<T, DB: OrmDB> SqlBuilder<DB> for T
 where T: TableSelector
 {
@@ -107,69 +222,70 @@ where T: TableSelector
         let mut placeholders = Vec::new();
         let mut i = 0;
         for col in cols.iter() {
+            if (col.auto_create || col.auto_update) && !self.is_field_set(col.name) {
+                insert_cols.push(DB::quote_ident(col.name));
+                placeholders.push("CURRENT_TIMESTAMP".to_string());
+                continue;
+            }
+            if col.is_version && !self.is_field_set(col.name) {
+                insert_cols.push(DB::quote_ident(col.name));
+                placeholders.push(INITIAL_VERSION.to_string());
+                continue;
+            }
             if !col.nullable && col.default.is_none() && !self.is_field_set(col.name) {
                 return Err(OrmError::MissingValue(col.name));
             }
 
             if self.is_field_set(col.name) || (!col.nullable && col.default.is_none()) {
-                insert_cols.push(col.name);
+                insert_cols.push(DB::quote_ident(col.name));
                 placeholders.push(DB::placeholder(i));
                 i += 1;
             }
         }
 
         let sql = format!(
-            "INSERT INTO {} ({}) VALUES ({}) RETURNING *",
+            "INSERT INTO {} ({}) VALUES ({}){}",
             table,
             insert_cols.join(", "),
-            placeholders.join(", ")
+            placeholders.join(", "),
+            returning::<DB>()
         );
 
         Ok(sql)
     }
 
-    fn update_for(&self) -> Result<String, OrmError> {
+    fn update_with_bind_order(&self) -> Result<(String, Vec<&'static str>), OrmError> {
         let table = DB::full_table_name(Self::TABLE_SCHEMA, Self::TABLE_NAME);
-        let cols = Self::columns();
-
-        let mut set_clauses = Vec::new();
-        let mut idx = 0;
-
-        let mut pk_col = None;
-        let mut pk_idx = 0;
-        for col in cols.iter() {
-            tracing::info!("Column: {}", col.name);
-            if col.is_primary {
-                pk_col = Some(col.name);
-                pk_idx = idx;
-                idx += 1;
-                continue;
-            }
-            if self.is_field_set(col.name) {
-                set_clauses.push(format!("{} = {}", col.name, DB::placeholder(idx)));
-                idx += 1;
-            }
+        let (mut set_cols, pk_col, version) = update_columns(self)?;
+
+        let mut set_clauses: Vec<String> = set_cols
+            .iter()
+            .enumerate()
+            .map(|(i, c)| format!("{} = {}", DB::quote_ident(c), DB::placeholder(i)))
+            .collect();
+        for col in Self::columns().iter().filter(|c| c.auto_update) {
+            set_clauses.push(format!("{} = CURRENT_TIMESTAMP", DB::quote_ident(col.name)));
         }
 
-        if set_clauses.is_empty() {
-            return Err(OrmError::NothingToUpdate);
-        }
-
-        let pk_col = pk_col
-            .ok_or(OrmError::MissingPrimaryKey)?;
-        if !self.is_field_set(pk_col) {
-            return Err(OrmError::MissingValue(pk_col));
+        let idx = set_cols.len();
+        let mut condition = format!("{} = {}", DB::quote_ident(pk_col), DB::placeholder(idx));
+        if let Some(version_col) = version {
+            let version_col = DB::quote_ident(version_col);
+            set_clauses.push(format!("{0} = {0} + 1", version_col));
+            condition = format!("{} AND {} = {}", condition, version_col, DB::placeholder(idx + 1));
         }
 
         let sql = format!(
-            "UPDATE {} SET {} WHERE {} = {} RETURNING *",
+            "UPDATE {} SET {} WHERE {}{}",
             table,
             set_clauses.join(", "),
-            pk_col,
-            DB::placeholder(pk_idx)
+            condition,
+            returning::<DB>()
         );
 
-        Ok(sql)
+        set_cols.push(pk_col);
+        set_cols.extend(version);
+        Ok((sql, set_cols))
     }
 
     fn upsert_for(&self) -> Result<String, OrmError> {
@@ -178,7 +294,7 @@ where T: TableSelector
 
         let mut insert_cols = Vec::new();
         let mut placeholders = Vec::new();
-        let mut update_clauses = Vec::new();
+        let mut update_cols = Vec::new();
         let mut idx = 0;
 
         let mut pk_col = None;
@@ -187,15 +303,30 @@ where T: TableSelector
             if col.is_primary {
                 pk_col = Some(col.name);
             }
+            if (col.auto_create || col.auto_update) && !self.is_field_set(col.name) {
+                insert_cols.push(DB::quote_ident(col.name));
+                placeholders.push("CURRENT_TIMESTAMP".to_string());
+                if col.auto_update {
+                    update_cols.push(col.name);
+                }
+                continue;
+            }
+            if col.is_version && !self.is_field_set(col.name) {
+                insert_cols.push(DB::quote_ident(col.name));
+                placeholders.push(INITIAL_VERSION.to_string());
+                continue;
+            }
             if !col.nullable && col.default.is_none() && !self.is_field_set(col.name) {
                 return Err(OrmError::MissingValue(col.name));
             }
 
             if self.is_field_set(col.name) || (!col.nullable && col.default.is_none()) {
-                insert_cols.push(col.name);
+                insert_cols.push(DB::quote_ident(col.name));
                 placeholders.push(DB::placeholder(idx));
                 idx += 1;
-                update_clauses.push(format!("{} = EXCLUDED.{}", col.name, col.name));
+                if !col.auto_create {
+                    update_cols.push(col.name);
+                }
             }
         }
 
@@ -204,44 +335,101 @@ where T: TableSelector
         }
         let pk_col = pk_col.ok_or(OrmError::MissingPrimaryKey)?;
         let sql = format!(
-            "INSERT INTO {} ({}) VALUES ({}) \
-            ON CONFLICT ({}) DO UPDATE SET {} \
-            RETURNING *",
+            "INSERT INTO {} ({}) VALUES ({}) {}{}",
             table,
             insert_cols.join(", "),
             placeholders.join(", "),
-            pk_col,
-            update_clauses.join(", ")
+            DB::upsert_clause(pk_col, &update_cols),
+            returning::<DB>()
         );
 
         Ok(sql)
     }
 
 
-    fn select_by_pk() -> String {
+    fn select_by_pk_scoped(scope: DeletedScope) -> String {
         let table = DB::full_table_name(Self::TABLE_SCHEMA, Self::TABLE_NAME);
-        let col_names: Vec<&str> = Self::columns().iter().map(|c| c.name).collect();
-        format!(
+        let col_names: Vec<String> = Self::columns().iter().map(|c| DB::quote_ident(c.name)).collect();
+        let mut sql = format!(
             "SELECT {} FROM {} WHERE {} = {}",
             col_names.join(", "),
             table,
-            Self::pk_column(),
+            DB::quote_ident(Self::pk_column()),
             DB::placeholder(0)
-        )
+        );
+        if let Some(cond) = <Self as SqlBuilder<DB>>::scope_condition(scope) {
+            sql = format!("{} AND {}", sql, cond);
+        }
+        sql
     }
 
     fn delete_by_pk() -> String {
+        let Some(col) = Self::soft_delete_column() else {
+            return <Self as SqlBuilder<DB>>::force_delete_by_pk();
+        };
         let table = DB::full_table_name(Self::TABLE_SCHEMA, Self::TABLE_NAME);
         format!(
-            "DELETE FROM {} WHERE {} = {} RETURNING *",
+            "UPDATE {} SET {} = CURRENT_TIMESTAMP WHERE {} = {} AND {} IS NULL{}",
             table,
-            Self::pk_column(),
-            DB::placeholder(0)
+            DB::quote_ident(col),
+            DB::quote_ident(Self::pk_column()),
+            DB::placeholder(0),
+            DB::quote_ident(col),
+            returning::<DB>()
         )
     }
 
-    fn count() -> String {
+    fn force_delete_by_pk() -> String {
+        let table = DB::full_table_name(Self::TABLE_SCHEMA, Self::TABLE_NAME);
+        format!(
+            "DELETE FROM {} WHERE {} = {}{}",
+            table,
+            DB::quote_ident(Self::pk_column()),
+            DB::placeholder(0),
+            returning::<DB>()
+        )
+    }
+
+    fn restore_by_pk() -> Result<String, OrmError> {
+        let col = Self::soft_delete_column().ok_or(OrmError::NotSoftDeletable)?;
+        let table = DB::full_table_name(Self::TABLE_SCHEMA, Self::TABLE_NAME);
+        Ok(format!(
+            "UPDATE {0} SET {1} = NULL WHERE {2} = {3} AND {1} IS NOT NULL{4}",
+            table,
+            DB::quote_ident(col),
+            DB::quote_ident(Self::pk_column()),
+            DB::placeholder(0),
+            returning::<DB>()
+        ))
+    }
+
+    fn count_scoped(scope: DeletedScope) -> String {
+        let table = DB::full_table_name(Self::TABLE_SCHEMA, Self::TABLE_NAME);
+        match <Self as SqlBuilder<DB>>::scope_condition(scope) {
+            Some(cond) => format!("SELECT COUNT(*) as cnt FROM {} WHERE {}", table, cond),
+            None => format!("SELECT COUNT(*) as cnt FROM {}", table),
+        }
+    }
+
+    fn select_last_inserted() -> Result<String, OrmError> {
+        let last_id = DB::last_insert_id().ok_or(OrmError::MissingValue(Self::pk_column()))?;
         let table = DB::full_table_name(Self::TABLE_SCHEMA, Self::TABLE_NAME);
-        format!("SELECT COUNT(*) as cnt FROM {}", table)
+        let col_names: Vec<String> = Self::columns().iter().map(|c| DB::quote_ident(c.name)).collect();
+        Ok(format!(
+            "SELECT {} FROM {} WHERE {} = {}",
+            col_names.join(", "),
+            table,
+            DB::quote_ident(Self::pk_column()),
+            last_id
+        ))
+    }
+
+    fn scope_condition(scope: DeletedScope) -> Option<String> {
+        let col = DB::quote_ident(Self::soft_delete_column()?);
+        match scope {
+            DeletedScope::Alive => Some(format!("{} IS NULL", col)),
+            DeletedScope::WithDeleted => None,
+            DeletedScope::OnlyDeleted => Some(format!("{} IS NOT NULL", col)),
+        }
     }
 }
@@ -1,15 +1,21 @@
 use std::marker::PhantomData;
 
-use sqlx::{Executor, FromRow, IntoArguments, query::QueryAs};
+use sqlx::{Acquire, Executor, FromRow, IntoArguments, query::{Query, QueryAs}};
 
-use crate::prelude::OrmDB;
+use crate::prelude::{OrmDB, OrmError, SqlBuilder};
 
 pub struct ColumnDef {
     pub name: &'static str,
     pub nullable: bool,
     pub is_unique: bool,
     pub default: Option<&'static str>,
-    pub is_primary: bool
+    pub is_primary: bool,
+    /// Optimistic locking counter
+    pub is_version: bool,
+    /// Filled with `CURRENT_TIMESTAMP` on insert unless set, never updated
+    pub auto_create: bool,
+    /// Filled with `CURRENT_TIMESTAMP` on insert unless set and on every update
+    pub auto_update: bool,
 }
 
 pub trait TableSelector {
@@ -18,7 +24,39 @@ pub trait TableSelector {
     type TypePK;
     fn columns() -> &'static [ColumnDef];
     fn pk_column() -> &'static str;
+    /// Primary key of the model if it is set
+    fn pk_value(&self) -> Option<&Self::TypePK>;
+    /// `false` for unknown fields as well
     fn is_field_set(&self, field_name: &str) -> bool;
+    /// Set and differs from the loaded value, models without one treat every set field as changed
+    fn is_field_dirty(&self, field_name: &str) -> bool {
+        self.is_field_set(field_name)
+    }
+    fn version_column() -> Option<&'static str> {
+        Self::columns().iter().find(|c| c.is_version).map(|c| c.name)
+    }
+    /// Timestamp column set by delete instead of removing the row
+    fn soft_delete_column() -> Option<&'static str> {
+        None
+    }
+}
+
+/// Rows of a soft deleted table visible to selects and counts
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DeletedScope {
+    #[default]
+    Alive,
+    WithDeleted,
+    OnlyDeleted,
+}
+
+/// Changed field of an `Active*` model, values are `Debug` formatted
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    /// `None` for models which were not loaded from a row
+    pub old: Option<String>,
+    pub new: String,
 }
 
 pub enum SaveMode {
@@ -29,8 +67,50 @@ pub enum SaveMode {
 
 pub use SaveMode::*;
 
+/// Query a value can be bound to, implemented for `Query` and `QueryAs`
+pub trait BindQuery<'q, DB: sqlx::Database>: Sized {
+    fn bind_value<V: 'q + sqlx::Encode<'q, DB> + sqlx::Type<DB>>(self, value: V) -> Self;
+}
+
+impl<'q, DB: sqlx::Database> BindQuery<'q, DB> for Query<'q, DB, <DB as sqlx::Database>::Arguments<'q>> {
+    fn bind_value<V: 'q + sqlx::Encode<'q, DB> + sqlx::Type<DB>>(self, value: V) -> Self {
+        self.bind(value)
+    }
+}
+
+impl<'q, DB: sqlx::Database, O> BindQuery<'q, DB> for QueryAs<'q, DB, O, <DB as sqlx::Database>::Arguments<'q>> {
+    fn bind_value<V: 'q + sqlx::Encode<'q, DB> + sqlx::Type<DB>>(self, value: V) -> Self {
+        self.bind(value)
+    }
+}
+
+/// Binds the set fields of a model, implemented by generated models for every database their field types support
+pub trait BindValues<DB: OrmDB>: TableSelector {
+    /// Binds the value of the column if it is set, unset and unknown columns leave the query unchanged
+    fn bind_column<'q, Q: BindQuery<'q, DB>>(&'q self, q: Q, column: &str) -> Q;
+    /// Set columns in column order, the order of `insert_for` and `upsert_for`
+    fn bind_values<'q, Q: BindQuery<'q, DB>>(&'q self, q: Q) -> Q {
+        Self::columns().iter().fold(q, |q, c| self.bind_column(q, c.name))
+    }
+    /// Set columns in the given order, e.g. `SqlBuilder::update_bind_order`
+    fn bind_columns<'q, Q: BindQuery<'q, DB>>(&'q self, q: Q, columns: &[&str]) -> Q {
+        columns.iter().fold(q, |q, c| self.bind_column(q, c))
+    }
+}
+
+/// Anything the writes of `ModelOps` can get a connection from: `&Pool`, `&mut Transaction`,
+/// `&mut PoolConnection` or `&mut` connection. Hooks and follow-up selects run on the same connection,
+/// so a plain `Executor` is not enough. Generic code can use it as the only bound of the executor
+/// ```ignore
+/// async fn create<'e, DB: OrmDB, E: OrmAcquire<'e, DB>>(user: ActiveUsers, exec: E) -> anyhow::Result<()>
+/// where ActiveUsers: ModelOps<DB> { .. }
+/// ```
+pub trait OrmAcquire<'e, DB: OrmDB>: Acquire<'e, Database = DB> + Send {}
+
+impl<'e, DB: OrmDB, T: Acquire<'e, Database = DB> + Send> OrmAcquire<'e, DB> for T {}
+
 #[async_trait::async_trait]
-pub trait ModelOps<DB>: Sized + TableSelector
+pub trait ModelOps<DB>: Sized + TableSelector + BindValues<DB>
 where
     DB: OrmDB,
     Self::NonActive : for<'r> FromRow<'r, <DB as sqlx::Database>::Row>,
@@ -38,24 +118,31 @@ where
     type NonActive;
     fn save<'e, E>(self, exec: E, mode: SaveMode) -> impl std::future::Future<Output = Result<Option<Self::NonActive>, anyhow::Error>> + Send
     where
-        E: Executor<'e, Database = DB>,
+        E: OrmAcquire<'e, DB>,
         for<'q> <DB as sqlx::Database>::Arguments<'q>: Default + sqlx::IntoArguments<'q, DB>
         ;
     fn complete_query<'s, 'q, T>(&'s self, q: QueryAs<'q, DB, T, <DB as sqlx::Database>::Arguments<'q>>)
-    -> sqlx::query::QueryAs<'q,sqlx::Postgres,T, <sqlx::Postgres as sqlx::Database> ::Arguments<'q> > where 's: 'q;
+    -> QueryAs<'q, DB, T, <DB as sqlx::Database>::Arguments<'q>> where 's: 'q {
+        self.bind_values(q)
+    }
+    /// Same as `complete_query` for statements executed without reading rows
+    fn complete_plain_query<'s, 'q>(&'s self, q: Query<'q, DB, <DB as sqlx::Database>::Arguments<'q>>)
+    -> Query<'q, DB, <DB as sqlx::Database>::Arguments<'q>> where 's: 'q {
+        self.bind_values(q)
+    }
     fn insert<'e, E>(self, exec: E) -> impl std::future::Future<Output = Result<Option<Self::NonActive>, anyhow::Error>> + Send
     where
-        E: Executor<'e, Database = DB>,
+        E: OrmAcquire<'e, DB>,
         for<'q> <DB as sqlx::Database>::Arguments<'q>: Default + sqlx::IntoArguments<'q, DB>
         ;
     fn update<'e, E>(self, exec: E) -> impl std::future::Future<Output = Result<Option<Self::NonActive>, anyhow::Error>> + Send
     where
-        E: Executor<'e, Database = DB>,
+        E: OrmAcquire<'e, DB>,
         for<'q> <DB as sqlx::Database>::Arguments<'q>: Default + sqlx::IntoArguments<'q, DB>
         ;
     fn upsert<'e, E>(self, exec: E) -> impl std::future::Future<Output = Result<Option<Self::NonActive>, anyhow::Error>> + Send
     where
-        E: Executor<'e, Database = DB>,
+        E: OrmAcquire<'e, DB>,
         for<'q> <DB as sqlx::Database>::Arguments<'q>: Default + sqlx::IntoArguments<'q, DB>
         ;
     fn select_by_pk<'e, E>(pk: &Self::TypePK, exec: E) -> impl std::future::Future<Output = Result<Option<Self::NonActive>, anyhow::Error>> + Send
@@ -63,14 +150,124 @@ where
         E: Executor<'e, Database = DB>,
         Self: for<'r> FromRow<'r, <DB as sqlx::Database>::Row>
         ;
+    fn select_by_pk_scoped<'e, E>(pk: &Self::TypePK, scope: DeletedScope, exec: E) -> impl std::future::Future<Output = Result<Option<Self::NonActive>, anyhow::Error>> + Send
+    where
+        E: Executor<'e, Database = DB>,
+        Self: for<'r> FromRow<'r, <DB as sqlx::Database>::Row>
+        ;
+    /// Soft deletes rows of tables with a soft delete column
     fn delete_by_pk<'e, E>(pk: &Self::TypePK, exec: E) -> impl std::future::Future<Output = Result<Option<Self::NonActive>, anyhow::Error>> + Send
     where
-        E: Executor<'e, Database = DB>;
+        E: OrmAcquire<'e, DB>;
+    fn force_delete_by_pk<'e, E>(pk: &Self::TypePK, exec: E) -> impl std::future::Future<Output = Result<Option<Self::NonActive>, anyhow::Error>> + Send
+    where
+        E: OrmAcquire<'e, DB>;
+    fn restore_by_pk<'e, E>(pk: &Self::TypePK, exec: E) -> impl std::future::Future<Output = Result<Option<Self::NonActive>, anyhow::Error>> + Send
+    where
+        E: OrmAcquire<'e, DB>;
     fn count<'e, E>(exec: E) -> impl std::future::Future<Output = Result<i64, anyhow::Error>> + Send
     where
         E: Executor<'e, Database = DB>;
+    fn count_scoped<'e, E>(scope: DeletedScope, exec: E) -> impl std::future::Future<Output = Result<i64, anyhow::Error>> + Send
+    where
+        E: Executor<'e, Database = DB>;
 }
 
+/// Rows written by statements without `RETURNING`, selected again on the same connection.
+/// Implemented by generated models
+pub trait WrittenRows<DB>: ModelOps<DB>
+where
+    DB: OrmDB,
+    Self::NonActive: for<'r> FromRow<'r, <DB as sqlx::Database>::Row>,
+{
+    /// Row of the model by its primary key, or the last inserted one if the key was generated
+    fn select_written(&self, conn: &mut <DB as sqlx::Database>::Connection) -> impl std::future::Future<Output = Result<Option<Self::NonActive>, anyhow::Error>> + Send;
+    /// Runs a statement bound to the primary key, rows are selected before deletes and after updates
+    fn write_by_pk(sql: &str, pk: &Self::TypePK, select_after: bool, conn: &mut <DB as sqlx::Database>::Connection) -> impl std::future::Future<Output = Result<Option<Self::NonActive>, anyhow::Error>> + Send;
+}
+
+/// Extension point around the write operations of `ModelOps`, e.g. for normalization,
+/// validation or outbox events. Generated models get an empty impl unless the table sets `hooks: true`.
+/// Upserts run the insert hooks, soft and forced deletes the delete hooks
+pub trait ModelHooks<DB>: ModelOps<DB>
+where
+    DB: OrmDB,
+    Self::NonActive: for<'r> FromRow<'r, <DB as sqlx::Database>::Row>,
+{
+    fn before_insert(&mut self, _conn: &mut <DB as sqlx::Database>::Connection) -> impl std::future::Future<Output = Result<(), anyhow::Error>> + Send {
+        async { Ok(()) }
+    }
+    fn after_insert(_row: &Self::NonActive, _conn: &mut <DB as sqlx::Database>::Connection) -> impl std::future::Future<Output = Result<(), anyhow::Error>> + Send {
+        async { Ok(()) }
+    }
+    fn before_update(&mut self, _conn: &mut <DB as sqlx::Database>::Connection) -> impl std::future::Future<Output = Result<(), anyhow::Error>> + Send {
+        async { Ok(()) }
+    }
+    fn after_update(_row: &Self::NonActive, _conn: &mut <DB as sqlx::Database>::Connection) -> impl std::future::Future<Output = Result<(), anyhow::Error>> + Send {
+        async { Ok(()) }
+    }
+    fn before_delete(_pk: &Self::TypePK, _conn: &mut <DB as sqlx::Database>::Connection) -> impl std::future::Future<Output = Result<(), anyhow::Error>> + Send {
+        async { Ok(()) }
+    }
+    fn after_delete(_row: &Self::NonActive, _conn: &mut <DB as sqlx::Database>::Connection) -> impl std::future::Future<Output = Result<(), anyhow::Error>> + Send {
+        async { Ok(()) }
+    }
+}
+
+/// Full statements are run as is, except on soft delete tables where they would see or remove deleted rows
+fn raw_source<DB: OrmDB, T: TableSelector>(prefix: &'static str, scope: DeletedScope, query: &str) -> Result<String, OrmError> {
+    if !query.to_ascii_lowercase().trim().starts_with(prefix) {
+        return Ok(scoped_source::<DB, T>(prefix, scope, query));
+    }
+    match (prefix, T::soft_delete_column(), scope) {
+        (_, None, _) | ("select", Some(_), DeletedScope::WithDeleted) => Ok(query.to_string()),
+        _ => Err(OrmError::UnscopedStatement(prefix)),
+    }
+}
+
+/// Source of `select` and `delete` queries given without the statement prefix
+fn scoped_source<DB: OrmDB, T: TableSelector>(prefix: &str, scope: DeletedScope, query: &str) -> String {
+    let table = DB::full_table_name(T::TABLE_SCHEMA, T::TABLE_NAME);
+    match (prefix, T::soft_delete_column()) {
+        ("delete", Some(col)) => {
+            let col = DB::quote_ident(col);
+            match split_where(query) {
+                Some((cond, tail)) => format!("update {} set {1} = CURRENT_TIMESTAMP where {1} IS NULL AND ({2}) {3}", table, col, cond, tail),
+                None => format!("update {} set {1} = CURRENT_TIMESTAMP where {1} IS NULL {2}", table, col, query),
+            }
+        }
+        ("delete", None) => format!("delete from {} {}", table, query),
+        _ => match <T as SqlBuilder<DB>>::scope_condition(scope) {
+            Some(cond) => format!("{} * from (select * from {} where {}) as {} {}", prefix, table, cond, DB::quote_ident(T::TABLE_NAME), query),
+            None => format!("{} * from {} {}", prefix, table, query),
+        },
+    }
+}
+
+/// Condition of a query starting with `where` and the clauses after it, e.g. `returning *`
+fn split_where(query: &str) -> Option<(&str, &str)> {
+    const TAIL: [&str; 3] = ["returning", "order by", "limit"];
+    let query = query.trim();
+    let lower = query.to_ascii_lowercase();
+    let is_ident = |c: u8| c.is_ascii_alphanumeric() || c == b'_';
+    if !lower.starts_with("where") || lower.as_bytes().get(5).is_some_and(|c| is_ident(*c)) {
+        return None;
+    }
+    let bytes = lower.as_bytes();
+    let (mut depth, mut quoted) = (0, false);
+    for i in 5..bytes.len() {
+        match bytes[i] {
+            b'\'' => quoted = !quoted,
+            b'(' if !quoted => depth += 1,
+            b')' if !quoted => depth -= 1,
+            _ if quoted || depth > 0 || is_ident(bytes[i - 1]) => {}
+            _ => if TAIL.iter().any(|kw| lower[i..].starts_with(kw) && !bytes.get(i + kw.len()).is_some_and(|c| is_ident(*c))) {
+                return Some((query[5..i].trim(), &query[i..]));
+            }
+        }
+    }
+    Some((query[5..].trim(), ""))
+}
 
 pub struct DBSelector<'e, DB, E, T>
 where
@@ -81,6 +278,7 @@ where
     pub(crate) _g: PhantomData<DB>,
     pub(crate) _t: PhantomData<T>,
     pub(crate) q_src: String,
+    pub(crate) scope: DeletedScope,
     pub(crate) executor: &'e E
 }
 
@@ -90,7 +288,8 @@ where
     &'e Ex: Executor<'e, Database = DB>,
 {
     pub(crate) q: QueryAs<'q, DB, Out, <DB as sqlx::Database>::Arguments<'q>>,
-    pub(crate) executor: &'e Ex
+    pub(crate) executor: &'e Ex,
+    pub(crate) error: Option<OrmError>,
 }
 
 impl<'e, DB, E, T> DBSelector<'e, DB, E, T>
@@ -105,13 +304,29 @@ where
     ) -> Self {
         Self {
             q_src: Default::default(),
+            scope: Default::default(),
             _g: PhantomData,
             _t: PhantomData,
             executor
         }
     }
     
-    pub fn save(self, data: T, mode: SaveMode) -> impl std::future::Future<Output = Result<Option<<T as ModelOps<DB>>::NonActive>, anyhow::Error>> + Send {
+    /// Soft deleted rows are visible too
+    pub fn with_deleted(mut self) -> Self {
+        self.scope = DeletedScope::WithDeleted;
+        self
+    }
+
+    /// Only soft deleted rows are visible
+    pub fn only_deleted(mut self) -> Self {
+        self.scope = DeletedScope::OnlyDeleted;
+        self
+    }
+
+    pub fn save(self, data: T, mode: SaveMode) -> impl std::future::Future<Output = Result<Option<<T as ModelOps<DB>>::NonActive>, anyhow::Error>> + Send
+    where
+        &'e E: OrmAcquire<'e, DB>,
+    {
         data.save(self.executor, mode)
     }
 
@@ -131,6 +346,8 @@ where
         self.interaction_builder("select", query)
     }
 
+    /// Soft deletes of a raw query only mark rows which are not deleted yet,
+    /// a full `delete` statement fails on soft delete tables, use `force_delete` instead
     pub fn delete<'q>(&'e mut self, query: &str) -> DBSelectorInteraction<'q, 'e, DB, E, T>
     where 
         'e: 'q, 
@@ -139,31 +356,48 @@ where
         self.interaction_builder("delete", query)
     }
 
-    fn interaction_builder<'q, O>(&'e mut self, prefix: &str, query: &str) -> DBSelectorInteraction<'q, 'e, DB, E, O>
+    fn interaction_builder<'q, O>(&'e mut self, prefix: &'static str, query: &str) -> DBSelectorInteraction<'q, 'e, DB, E, O>
     where 
         'e: 'q, 
         for<'r> O: FromRow<'r, <DB as sqlx::Database>::Row>
     {
-        let q_src = if query.to_ascii_lowercase().trim().starts_with(prefix) {
-            query.to_string()
-        } else {
-            format!("{} * from {}.{} {}", prefix, T::TABLE_SCHEMA, T::TABLE_NAME, query)
+        let (q_src, error) = match raw_source::<DB, T>(prefix, self.scope, query) {
+            Ok(q_src) => (q_src, None),
+            Err(e) => (String::new(), Some(e)),
         };
         self.q_src = q_src;
         DBSelectorInteraction {
             q: sqlx::query_as::<DB, O>(&self.q_src),
-            executor: self.executor
+            executor: self.executor,
+            error,
         }
     }
     
     pub fn select_by_pk(self, key: &T::TypePK) -> impl std::future::Future<Output = Result<Option<<T as ModelOps<DB>>::NonActive>, anyhow::Error>> + Send {
-        T::select_by_pk(key, self.executor)
+        T::select_by_pk_scoped(key, self.scope, self.executor)
     }
-    pub fn delete_by_pk(self, key: &T::TypePK) -> impl std::future::Future<Output = Result<Option<<T as ModelOps<DB>>::NonActive>, anyhow::Error>> + Send {
+    pub fn delete_by_pk(self, key: &T::TypePK) -> impl std::future::Future<Output = Result<Option<<T as ModelOps<DB>>::NonActive>, anyhow::Error>> + Send
+    where
+        &'e E: OrmAcquire<'e, DB>,
+    {
         T::delete_by_pk(key, self.executor)
     }
+    /// Removes the row even if the table is soft deleted
+    pub fn force_delete(self, key: &T::TypePK) -> impl std::future::Future<Output = Result<Option<<T as ModelOps<DB>>::NonActive>, anyhow::Error>> + Send
+    where
+        &'e E: OrmAcquire<'e, DB>,
+    {
+        T::force_delete_by_pk(key, self.executor)
+    }
+    /// Clears the soft delete column of the row
+    pub fn restore(self, key: &T::TypePK) -> impl std::future::Future<Output = Result<Option<<T as ModelOps<DB>>::NonActive>, anyhow::Error>> + Send
+    where
+        &'e E: OrmAcquire<'e, DB>,
+    {
+        T::restore_by_pk(key, self.executor)
+    }
     pub fn count(self) -> impl std::future::Future<Output = Result<i64, anyhow::Error>> + Send {
-        T::count(self.executor)
+        T::count_scoped(self.scope, self.executor)
     }
 }
 
@@ -185,6 +419,9 @@ where
     where
         Out: Send + Unpin + for<'r> FromRow<'r, <DB as sqlx::Database>::Row>
     {
+        if let Some(e) = self.error {
+            return Err(e.into());
+        }
         Ok(self.q.fetch_all(self.executor).await?)
     }
 }
@@ -198,6 +435,7 @@ where
     pub(crate) _g: PhantomData<DB>,
     pub(crate) _t: PhantomData<T>,
     pub(crate) q_src: String,
+    pub(crate) scope: DeletedScope,
     pub(crate) executor: &'e mut <DB as sqlx::Database>::Connection
 }
 
@@ -206,7 +444,8 @@ where
     DB: OrmDB,
 {
     pub(crate) q: QueryAs<'q, DB, Out, <DB as sqlx::Database>::Arguments<'q>>,
-    pub(crate) executor: &'e mut <DB as sqlx::Database>::Connection
+    pub(crate) executor: &'e mut <DB as sqlx::Database>::Connection,
+    pub(crate) error: Option<OrmError>,
 }
 
 
@@ -220,13 +459,29 @@ where
     pub fn new(executor: &'e mut <DB as sqlx::Database>::Connection) -> Self {
         Self {
             q_src: Default::default(),
+            scope: Default::default(),
             _g: PhantomData,
             _t: PhantomData,
             executor
         }
     }
     
-    pub fn save(self, data: T, mode: SaveMode) -> impl std::future::Future<Output = Result<Option<<T as ModelOps<DB>>::NonActive>, anyhow::Error>> + Send {
+    /// Soft deleted rows are visible too
+    pub fn with_deleted(mut self) -> Self {
+        self.scope = DeletedScope::WithDeleted;
+        self
+    }
+
+    /// Only soft deleted rows are visible
+    pub fn only_deleted(mut self) -> Self {
+        self.scope = DeletedScope::OnlyDeleted;
+        self
+    }
+
+    pub fn save(self, data: T, mode: SaveMode) -> impl std::future::Future<Output = Result<Option<<T as ModelOps<DB>>::NonActive>, anyhow::Error>> + Send
+    where
+        &'e mut <DB as sqlx::Database>::Connection: OrmAcquire<'e, DB>,
+    {
         data.save(self.executor, mode)
     }
 
@@ -247,6 +502,8 @@ where
     }
 
 
+    /// Soft deletes of a raw query only mark rows which are not deleted yet,
+    /// a full `delete` statement fails on soft delete tables, use `force_delete` instead
     pub fn delete<'q>(&'e mut self, query: &str) -> TxSelectorInteraction<'q, 'e, DB, T>
     where 
         'e: 'q, 
@@ -255,31 +512,48 @@ where
         self.interaction_builder("delete", query)
     }
 
-    fn interaction_builder<'q, O>(&'e mut self, prefix: &str, query: &str) -> TxSelectorInteraction<'q, 'e, DB, O>
+    fn interaction_builder<'q, O>(&'e mut self, prefix: &'static str, query: &str) -> TxSelectorInteraction<'q, 'e, DB, O>
     where 
         'e: 'q, 
         for<'r> O: FromRow<'r, <DB as sqlx::Database>::Row>
     {
-        let q_src = if query.to_ascii_lowercase().trim().starts_with(prefix) {
-            query.to_string()
-        } else {
-            format!("{} * from {}.{} {}", prefix, T::TABLE_SCHEMA, T::TABLE_NAME, query)
+        let (q_src, error) = match raw_source::<DB, T>(prefix, self.scope, query) {
+            Ok(q_src) => (q_src, None),
+            Err(e) => (String::new(), Some(e)),
         };
         self.q_src = q_src;
         TxSelectorInteraction {
             q: sqlx::query_as::<DB, O>(&self.q_src),
-            executor: self.executor
+            executor: self.executor,
+            error,
         }
     }
     
     pub fn select_by_pk(self, key: &T::TypePK) -> impl std::future::Future<Output = Result<Option<<T as ModelOps<DB>>::NonActive>, anyhow::Error>> + Send {
-        T::select_by_pk(key, self.executor)
+        T::select_by_pk_scoped(key, self.scope, self.executor)
     }
-    pub fn delete_by_pk(self, key: &T::TypePK) -> impl std::future::Future<Output = Result<Option<<T as ModelOps<DB>>::NonActive>, anyhow::Error>> + Send {
+    pub fn delete_by_pk(self, key: &T::TypePK) -> impl std::future::Future<Output = Result<Option<<T as ModelOps<DB>>::NonActive>, anyhow::Error>> + Send
+    where
+        &'e mut <DB as sqlx::Database>::Connection: OrmAcquire<'e, DB>,
+    {
         T::delete_by_pk(key, self.executor)
     }
+    /// Removes the row even if the table is soft deleted
+    pub fn force_delete(self, key: &T::TypePK) -> impl std::future::Future<Output = Result<Option<<T as ModelOps<DB>>::NonActive>, anyhow::Error>> + Send
+    where
+        &'e mut <DB as sqlx::Database>::Connection: OrmAcquire<'e, DB>,
+    {
+        T::force_delete_by_pk(key, self.executor)
+    }
+    /// Clears the soft delete column of the row
+    pub fn restore(self, key: &T::TypePK) -> impl std::future::Future<Output = Result<Option<<T as ModelOps<DB>>::NonActive>, anyhow::Error>> + Send
+    where
+        &'e mut <DB as sqlx::Database>::Connection: OrmAcquire<'e, DB>,
+    {
+        T::restore_by_pk(key, self.executor)
+    }
     pub fn count(self) -> impl std::future::Future<Output = Result<i64, anyhow::Error>> + Send {
-        T::count(self.executor)
+        T::count_scoped(self.scope, self.executor)
     }
 }
 
@@ -300,6 +574,9 @@ where
     where
         Out: Send + Unpin + for<'r> FromRow<'r, <DB as sqlx::Database>::Row>
     {
+        if let Some(e) = self.error {
+            return Err(e.into());
+        }
         Ok(self.q.fetch_all(self.executor).await?)
     }
 }
\ No newline at end of file
@@ -1,3 +1,8 @@
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+use sqlx::Acquire;
+
 use crate::prelude::OrmDB;
 
 #[derive(Clone)]
@@ -9,28 +14,174 @@ pub struct Orm<E : Clone>
 impl<DB : OrmDB> Orm<sqlx::Pool<DB>>
 {
     pub fn new(pool: sqlx::Pool<DB>) -> Self {
-        Self { 
+        Self {
             executor: pool,
         }
     }
 
    pub fn get_executor<'a, 'b>(&'a self) -> &'b sqlx::Pool<DB> where 'a: 'b { &self.executor }
 
-    pub async fn begin_tx(&self) -> Result<OrmTX<DB>, Box<dyn std::error::Error>> {
+    pub async fn begin_tx(&self) -> Result<OrmTX<'static, DB>, sqlx::Error> {
         let v = self.executor.begin().await?;
-        Ok(OrmTX { 
-            inner: v 
+        Ok(OrmTX {
+            inner: v
         })
     }
+
+    /// Same as `begin_tx`, the level is ignored by databases without a choice of isolation levels
+    pub async fn begin_tx_with(&self, isolation: IsolationLevel) -> Result<OrmTX<'static, DB>, sqlx::Error> {
+        let inner = DB::begin_isolated(&self.executor, isolation).await?;
+        Ok(OrmTX { inner })
+    }
+
+    /// Runs `f` in a transaction with default [`TxOptions`], see [`Orm::transaction_with`]
+    /// ```ignore
+    /// let inserted = orm.transaction(|tx| {
+    ///     let user = user.clone();
+    ///     Box::pin(async move { tx.users().save(user, SaveMode::Insert).await })
+    /// }).await?;
+    /// ```
+    pub async fn transaction<T, F>(&self, f: F) -> Result<T, anyhow::Error>
+    where
+        T: Send,
+        F: for<'t> FnMut(&'t mut OrmTX<'static, DB>) -> BoxFuture<'t, Result<T, anyhow::Error>> + Send,
+    {
+        self.transaction_with(TxOptions::default(), f).await
+    }
+
+    /// Commits when `f` returns `Ok` and rolls back when it returns `Err`, on panic the dropped transaction is rolled back.
+    /// The whole transaction is run again while it fails with an error retryable for the database and the retry policy allows
+    pub async fn transaction_with<T, F>(&self, options: TxOptions, mut f: F) -> Result<T, anyhow::Error>
+    where
+        T: Send,
+        F: for<'t> FnMut(&'t mut OrmTX<'static, DB>) -> BoxFuture<'t, Result<T, anyhow::Error>> + Send,
+    {
+        let mut attempt = 1;
+        loop {
+            match self.run_tx(&options, &mut f).await {
+                Err(e) if attempt < options.retry.max_attempts && is_retryable::<DB>(&e) => {
+                    tracing::debug!("Retrying transaction after attempt {}: {}", attempt, e);
+                    tokio::time::sleep(options.retry.delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn run_tx<T, F>(&self, options: &TxOptions, f: &mut F) -> Result<T, anyhow::Error>
+    where
+        T: Send,
+        F: for<'t> FnMut(&'t mut OrmTX<'static, DB>) -> BoxFuture<'t, Result<T, anyhow::Error>> + Send,
+    {
+        let mut tx = match options.isolation {
+            Some(isolation) => self.begin_tx_with(isolation).await?,
+            None => self.begin_tx().await?,
+        };
+        match f(&mut tx).await {
+            Ok(v) => {
+                tx.commit().await?;
+                Ok(v)
+            }
+            Err(e) => {
+                if let Err(rollback) = tx.rollback().await {
+                    tracing::error!("Can't rollback transaction: {}", rollback);
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+fn is_retryable<DB: OrmDB>(err: &anyhow::Error) -> bool {
+    match err.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::Database(e)) => DB::is_retryable(e.as_ref()),
+        _ => false,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IsolationLevel {
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+impl IsolationLevel {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            IsolationLevel::ReadUncommitted => "READ UNCOMMITTED",
+            IsolationLevel::ReadCommitted => "READ COMMITTED",
+            IsolationLevel::RepeatableRead => "REPEATABLE READ",
+            IsolationLevel::Serializable => "SERIALIZABLE",
+        }
+    }
 }
 
+/// How many times a transaction is run and how long to wait between runs, the delay doubles after every failed run
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Runs including the first one, `1` disables retries
+    pub max_attempts: u32,
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+}
 
-pub struct OrmTX<DB: OrmDB> {
-    pub inner: sqlx::Transaction<'static, DB>,
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+        }
+    }
 }
 
-impl<DB: OrmDB> OrmTX<DB> {
-    pub fn get_inner(&mut self) -> &mut <DB as sqlx::Database>::Connection { &mut *self.inner }
+impl RetryPolicy {
+    pub fn never() -> Self {
+        Self { max_attempts: 1, ..Default::default() }
+    }
+
+    /// Wait after the failed run number `attempt`, starting from 1
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.backoff.saturating_mul(1 << attempt.saturating_sub(1).min(16)).min(self.max_backoff)
+    }
+}
+
+/// Options of [`Orm::transaction_with`], by default the database isolation level is used and retries follow [`RetryPolicy::default`]
+#[derive(Clone, Debug, Default)]
+pub struct TxOptions {
+    pub isolation: Option<IsolationLevel>,
+    pub retry: RetryPolicy,
+}
+
+impl TxOptions {
+    pub fn isolation(mut self, isolation: IsolationLevel) -> Self {
+        self.isolation = Some(isolation);
+        self
+    }
+
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+}
+
+
+pub struct OrmTX<'c, DB: OrmDB> {
+    pub inner: sqlx::Transaction<'c, DB>,
+}
+
+impl<'c, DB: OrmDB> OrmTX<'c, DB> {
+    pub fn get_inner(&mut self) -> &mut <DB as sqlx::Database>::Connection { &mut self.inner }
+
+    /// Nested transaction, committing or rolling it back affects only the changes made through it.
+    /// Dropped without a commit it is rolled back
+    pub async fn savepoint(&mut self) -> Result<OrmTX<'_, DB>, sqlx::Error> {
+        let inner = (&mut self.inner).begin().await?;
+        Ok(OrmTX { inner })
+    }
 
     pub async fn commit(self) -> Result<(), sqlx::Error> {
         self.inner.commit().await
@@ -39,4 +190,3 @@ impl<DB: OrmDB> OrmTX<DB> {
         self.inner.rollback().await
     }
 }
-
@@ -18,6 +18,12 @@ pub const MOD_TEMPLATE : &str = include_str!("../templates/mod.hbr");
 pub const TABLE_TEMPLATE : &str = include_str!("../templates/table.hbr");
 
 pub const MIGRATION_TEMPLATE : &str = include_str!("../templates/migration.hbr");
+pub const SQLITE_MIGRATION_TEMPLATE : &str = include_str!("../templates/migration_sqlite.hbr");
+
+/// Starts every state file, files without it were written before the format was versioned
+const STATE_MAGIC: &[u8] = b"ORMSTATE";
+/// Bumped on every change of the encoded schema, older versions are converted on load
+const STATE_VERSION: u32 = 2;
 
 #[derive(Deserialize, Serialize, Debug, Default)]
 pub struct LatestMigrationState {
@@ -25,20 +31,204 @@ pub struct LatestMigrationState {
     state: Schema,
 }
 
+impl LatestMigrationState {
+    pub fn latest(&self) -> usize {
+        self.latest
+    }
+
+    pub fn state(&self) -> &Schema {
+        &self.state
+    }
+
+    /// Reads a state of any known version, fails instead of starting from scratch
+    pub fn decode(bytes: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
+        let config = bincode::config::standard();
+        let Some(rest) = bytes.strip_prefix(STATE_MAGIC) else {
+            let (state, _) = bincode::serde::decode_from_slice::<legacy::LatestMigrationState, _>(bytes, config)
+                .map_err(|e| format!("Can't decode unversioned migration state: {}", e))?;
+            return Ok(state.into());
+        };
+        let (version, read) = bincode::decode_from_slice::<u32, _>(rest, config)?;
+        match version {
+            STATE_VERSION => Ok(bincode::serde::decode_from_slice::<Self, _>(&rest[read..], config)
+                .map_err(|e| format!("Can't decode migration state v{}: {}", version, e))?
+                .0),
+            1 => Ok(bincode::serde::decode_from_slice::<v1::LatestMigrationState, _>(&rest[read..], config)
+                .map_err(|e| format!("Can't decode migration state v{}: {}", version, e))?
+                .0
+                .into()),
+            _ => Err(format!("Migration state v{} is newer than supported v{}", version, STATE_VERSION).into()),
+        }
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let config = bincode::config::standard();
+        let mut out = STATE_MAGIC.to_vec();
+        out.extend(bincode::encode_to_vec(STATE_VERSION, config)?);
+        out.extend(bincode::serde::encode_to_vec(self, config)?);
+        Ok(out)
+    }
+}
+
+/// State as written before versioning, the schema had no flags for newtypes, versions, timestamps, soft delete and hooks
+mod legacy {
+    use hashbrown::HashMap;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    pub struct LatestMigrationState {
+        latest: usize,
+        state: Schema,
+    }
+
+    #[derive(Deserialize)]
+    struct Schema {
+        tables: HashMap<String, Table>,
+        types: HashMap<String, Type>,
+        type_mapping: TypeMapping,
+    }
+
+    #[derive(Deserialize)]
+    enum TypeMapping {
+        Rust,
+        Pg,
+    }
+
+    #[derive(Deserialize)]
+    struct Type {
+        rust_type: String,
+        pg_type: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Table {
+        name: String,
+        schema: String,
+        fields: Vec<TypedField>,
+    }
+
+    #[derive(Deserialize)]
+    struct TypedField {
+        name: String,
+        type_name: String,
+        type_str: String,
+        is_primary: bool,
+        default: Option<String>,
+        nullable: bool,
+        is_unique: bool,
+    }
+
+    impl From<LatestMigrationState> for super::LatestMigrationState {
+        fn from(v: LatestMigrationState) -> Self {
+            use schema_reader::prelude as current;
+            let tables = v.state.tables.into_iter().map(|(k, t)| {
+                let fields = t.fields.into_iter().map(|f| current::TypedField {
+                    name: f.name,
+                    type_name: f.type_name,
+                    type_str: f.type_str,
+                    is_primary: f.is_primary,
+                    default: f.default,
+                    nullable: f.nullable,
+                    is_unique: f.is_unique,
+                    ..Default::default()
+                }).collect();
+                (k, current::Table { name: t.name, schema: t.schema, fields, ..Default::default() })
+            }).collect();
+            let types = v.state.types.into_iter().map(|(k, t)| {
+                (k, current::Type { rust_type: t.rust_type, pg_type: t.pg_type, ..Default::default() })
+            }).collect();
+            let type_mapping = match v.state.type_mapping {
+                TypeMapping::Rust => current::TypeMapping::Rust,
+                TypeMapping::Pg => current::TypeMapping::Pg,
+            };
+            Self { latest: v.latest, state: current::Schema::new(tables, types, type_mapping) }
+        }
+    }
+}
+
+/// State before fields could reference newtypes of other tables
+mod v1 {
+    use hashbrown::HashMap;
+    use schema_reader::prelude::{Type, TypeMapping};
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    pub struct LatestMigrationState {
+        latest: usize,
+        state: Schema,
+    }
+
+    #[derive(Deserialize)]
+    struct Schema {
+        tables: HashMap<String, Table>,
+        types: HashMap<String, Type>,
+        type_mapping: TypeMapping,
+    }
+
+    #[derive(Deserialize)]
+    struct Table {
+        name: String,
+        schema: String,
+        fields: Vec<TypedField>,
+        soft_delete: Option<String>,
+        hooks: bool,
+    }
+
+    #[derive(Deserialize)]
+    struct TypedField {
+        name: String,
+        type_name: String,
+        type_str: String,
+        is_primary: bool,
+        default: Option<String>,
+        nullable: bool,
+        is_unique: bool,
+        newtype: bool,
+        is_version: bool,
+        auto_create: bool,
+        auto_update: bool,
+    }
+
+    impl From<LatestMigrationState> for super::LatestMigrationState {
+        fn from(v: LatestMigrationState) -> Self {
+            use schema_reader::prelude as current;
+            let tables = v.state.tables.into_iter().map(|(k, t)| {
+                let fields = t.fields.into_iter().map(|f| current::TypedField {
+                    name: f.name,
+                    type_name: f.type_name,
+                    type_str: f.type_str,
+                    is_primary: f.is_primary,
+                    default: f.default,
+                    nullable: f.nullable,
+                    is_unique: f.is_unique,
+                    newtype: f.newtype,
+                    is_version: f.is_version,
+                    auto_create: f.auto_create,
+                    auto_update: f.auto_update,
+                    references: None,
+                }).collect();
+                (k, current::Table { name: t.name, schema: t.schema, fields, soft_delete: t.soft_delete, hooks: t.hooks })
+            }).collect();
+            Self { latest: v.latest, state: current::Schema::new(tables, v.state.types, v.state.type_mapping) }
+        }
+    }
+}
+
 pub fn generate_migration<P: AsRef<std::path::Path>>(mut schema : Schema, out_dir: P, migration_name: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
     let mut reg = handlebars::Handlebars::new();
     reg.register_template_string("migration_template", MIGRATION_TEMPLATE)?;
     schema.change_mappings(TypeMapping::Pg)?;
     let state_path = out_dir.as_ref().join("latest").with_extension("migration_state");
     
-    let mut prev_state = std::fs::read(&state_path)
-        .inspect_err(|_e| tracing::info!("No previous state found, starting from scratch"))
-        .ok()
-        .and_then(|v| 
-            bincode::serde::decode_from_slice::<LatestMigrationState, _>(&v, bincode::config::standard()).ok()
-                .map(|v|v.0)
-        )
-        .unwrap_or_default();
+    let mut prev_state = match std::fs::read(&state_path) {
+        Ok(v) => LatestMigrationState::decode(&v)
+            .inspect_err(|e| tracing::error!("Can't read state {}: {}", state_path.display(), e))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tracing::info!("No previous state found, starting from scratch");
+            LatestMigrationState::default()
+        }
+        Err(e) => return Err(e.into()),
+    };
     if schema == prev_state.state {
         tracing::info!("No changes in schema");
         return Ok(());
@@ -63,12 +253,21 @@ pub fn generate_migration<P: AsRef<std::path::Path>>(mut schema : Schema, out_di
         rendered
     )?;
     tracing::info!("Migration generated!");
-    let encoded = bincode::serde::encode_to_vec(&prev_state, bincode::config::standard()).inspect_err(|e| tracing::error!("Can't encode state: {}", e))?;
+    let encoded = prev_state.encode().inspect_err(|e| tracing::error!("Can't encode state: {}", e))?;
     std::fs::write(state_path, encoded)?;
     tracing::info!("Latest state saved!");
     Ok(())
 }
 
+/// SQL creating every table of the schema in an empty SQLite database, used by `orm::testing`
+pub fn render_sqlite_migration(mut schema: Schema) -> Result<String, Box<dyn std::error::Error>> {
+    let mut reg = handlebars::Handlebars::new();
+    reg.register_template_string("migration_template", SQLITE_MIGRATION_TEMPLATE)?;
+    schema.change_mappings(TypeMapping::Sqlite)?;
+    let diff = Schema::default().difference(&schema)?;
+    Ok(reg.render("migration_template", &diff)?)
+}
+
 pub fn generate_rust_bindings<P: AsRef<std::path::Path>>(schema : &Schema, out_dir: P) -> Result<(), Box<dyn std::error::Error>>{
     let mut reg = handlebars::Handlebars::new();
 
@@ -1,12 +1,15 @@
 pub mod abstractions;
 pub mod components;
 pub mod generators;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 #[allow(unused)]
 pub mod prelude {
     pub use super::components::prelude::*;
     pub use super::abstractions::prelude::*;
     pub use super::generators::*;
+    pub use utils::wrappers;
 }
 
 
@@ -0,0 +1,97 @@
+//! Offline tests of generated models: an in-memory SQLite [`Orm`] with the schema applied
+//! and fake values for the generated `{Table}Factory` builders.
+//!
+//! Factories are generated under `#[cfg(any(test, feature = "testing"))]`, so tests of the crate
+//! with the models only need `orm` with the `testing` feature in `dev-dependencies`:
+//! ```ignore
+//! let orm = orm::testing::sqlite_orm_from_dir("schema").await?;
+//! let user = UsersFactory::build().with_email("a@b.c").insert(&orm).await?;
+//! ```
+//! The `orm_tests` crate of the workspace generates models this way and runs them against SQLite.
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use schema_reader::prelude::Schema;
+use sqlx::{Pool, Sqlite, sqlite::SqlitePoolOptions};
+
+use crate::prelude::*;
+
+/// In-memory database with every table of the schema. The pool has a single connection
+/// which is never closed, so the data lives as long as the pool
+pub async fn sqlite_orm(schema: Schema) -> Result<Orm<Pool<Sqlite>>, anyhow::Error> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect("sqlite::memory:")
+        .await?;
+    let migration = render_sqlite_migration(schema).map_err(|e| anyhow::anyhow!("Can't render migration: {}", e))?;
+    sqlx::raw_sql(&migration).execute(&pool).await?;
+    Ok(Orm::new(pool))
+}
+
+/// Same as [`sqlite_orm`] with the schema read by `Schema::from_dir`
+pub async fn sqlite_orm_from_dir<P: AsRef<std::path::Path>>(path: P) -> Result<Orm<Pool<Sqlite>>, anyhow::Error> {
+    sqlite_orm(Schema::from_dir(path)?).await
+}
+
+static SEQ: AtomicU64 = AtomicU64::new(1);
+
+/// Next number of the process-wide sequence, unique for every built model
+pub fn next_seq() -> u64 {
+    SEQ.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Value of a required field filled in by factories, `n` is unique per built model
+pub trait Fake {
+    fn fake(n: u64) -> Self;
+}
+
+impl Fake for String {
+    fn fake(n: u64) -> Self {
+        format!("fake-{}", n)
+    }
+}
+
+macro_rules! fake_numbers {
+    ($($ty:ty),*) => {
+        $(
+            impl Fake for $ty {
+                fn fake(n: u64) -> Self {
+                    n as $ty
+                }
+            }
+        )*
+    };
+}
+
+fake_numbers!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64);
+
+impl Fake for bool {
+    fn fake(n: u64) -> Self {
+        n.is_multiple_of(2)
+    }
+}
+
+impl Fake for sqlx::types::Uuid {
+    fn fake(n: u64) -> Self {
+        sqlx::types::Uuid::from_u128(n as u128)
+    }
+}
+
+impl Fake for chrono::NaiveDateTime {
+    fn fake(n: u64) -> Self {
+        chrono::DateTime::<chrono::Utc>::fake(n).naive_utc()
+    }
+}
+
+impl Fake for chrono::NaiveDate {
+    fn fake(n: u64) -> Self {
+        chrono::NaiveDateTime::fake(n).date()
+    }
+}
+
+impl Fake for chrono::DateTime<chrono::Utc> {
+    fn fake(n: u64) -> Self {
+        chrono::DateTime::from_timestamp(n as i64, 0).unwrap_or_default()
+    }
+}
@@ -0,0 +1,26 @@
+use orm::generators::generate_migration;
+use schema_reader::prelude::Schema;
+
+#[test]
+fn test_reserved_identifiers_are_quoted() {
+    let dir = std::env::temp_dir().join(format!("orm-migration-sql-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let schema = Schema::from_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/reserved")).unwrap();
+    generate_migration(schema, &dir, Some("init")).unwrap();
+    let sql = std::fs::read_to_string(dir.join("V1__init.sql")).unwrap();
+    assert!(sql.contains(r#"CREATE SCHEMA IF NOT EXISTS "public";"#));
+    assert!(sql.contains(r#"CREATE TABLE IF NOT EXISTS "public"."user" ("#));
+    assert!(sql.contains(r#""order" text"#));
+    assert!(sql.contains("DEFAULT 'none'"));
+
+    let changed = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/reserved/schema.yaml")).unwrap()
+        + "      - name: group\n        type: text\n        nullable: true\n";
+    let schema_dir = dir.join("schema");
+    std::fs::create_dir_all(&schema_dir).unwrap();
+    std::fs::write(schema_dir.join("schema.yaml"), changed).unwrap();
+    generate_migration(Schema::from_dir(&schema_dir).unwrap(), &dir, Some("group")).unwrap();
+    let sql = std::fs::read_to_string(dir.join("V2__group.sql")).unwrap();
+    assert!(sql.contains(r#"ALTER TABLE "public"."user""#));
+    assert!(sql.contains(r#"ADD COLUMN "group" text"#));
+}
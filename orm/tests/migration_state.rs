@@ -0,0 +1,85 @@
+use std::path::{Path, PathBuf};
+
+use orm::generators::{generate_migration, LatestMigrationState};
+use schema_reader::prelude::Schema;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/migration_state");
+
+fn out_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("orm-migration-state-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn schema() -> Schema {
+    Schema::from_dir(Path::new(FIXTURES).join("schema")).unwrap()
+}
+
+fn baseline() -> Vec<u8> {
+    std::fs::read(Path::new(FIXTURES).join("baseline.migration_state")).unwrap()
+}
+
+fn migrations(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir).unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(".sql"))
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_baseline_state_is_decoded() {
+    let state = LatestMigrationState::decode(&baseline()).unwrap();
+    assert_eq!(state.latest(), 1);
+    let users = &state.state().get_tables()["users"];
+    let fields: Vec<&str> = users.fields.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(fields, vec!["id", "email", "nickname"]);
+    assert!(users.fields[0].is_primary);
+    assert!(users.fields[2].nullable);
+}
+
+#[test]
+fn test_v1_state_is_not_regenerated() {
+    let v1 = std::fs::read(Path::new(FIXTURES).join("v1.migration_state")).unwrap();
+    assert_eq!(LatestMigrationState::decode(&v1).unwrap().state().get_tables()["users"].fields.len(), 3);
+
+    let dir = out_dir("v1");
+    std::fs::write(dir.join("latest.migration_state"), v1).unwrap();
+    generate_migration(schema(), &dir, Some("next")).unwrap();
+    assert!(migrations(&dir).is_empty());
+}
+
+#[test]
+fn test_baseline_state_is_not_regenerated() {
+    let dir = out_dir("baseline");
+    std::fs::write(dir.join("latest.migration_state"), baseline()).unwrap();
+    generate_migration(schema(), &dir, Some("next")).unwrap();
+    assert!(migrations(&dir).is_empty());
+}
+
+#[test]
+fn test_state_round_trip() {
+    let dir = out_dir("round-trip");
+    generate_migration(schema(), &dir, Some("init")).unwrap();
+    assert_eq!(migrations(&dir), vec!["V1__init.sql"]);
+    let encoded = std::fs::read(dir.join("latest.migration_state")).unwrap();
+    assert!(encoded.starts_with(b"ORMSTATE"));
+    assert_eq!(LatestMigrationState::decode(&encoded).unwrap().latest(), 1);
+
+    generate_migration(schema(), &dir, Some("again")).unwrap();
+    assert_eq!(migrations(&dir), vec!["V1__init.sql"]);
+}
+
+#[test]
+fn test_corrupted_state_is_an_error() {
+    let dir = out_dir("corrupted");
+    std::fs::write(dir.join("latest.migration_state"), b"garbage").unwrap();
+    assert!(generate_migration(schema(), &dir, Some("init")).is_err());
+    assert!(migrations(&dir).is_empty());
+
+    let mut newer = b"ORMSTATE".to_vec();
+    newer.push(99);
+    assert!(LatestMigrationState::decode(&newer).unwrap_err().to_string().contains("v99"));
+}
@@ -0,0 +1,7 @@
+fn main() {
+    println!("cargo:rerun-if-changed=schema");
+    let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("models");
+    std::fs::create_dir_all(&out).unwrap();
+    let schema = schema_reader::prelude::Schema::from_dir("schema").unwrap();
+    orm::generators::generate_rust_bindings(&schema, &out).unwrap();
+}
@@ -0,0 +1,60 @@
+//! Models generated by `build.rs` from `schema/`, the tests of this crate run them against SQLite
+use std::sync::Mutex;
+
+use orm::prelude::*;
+
+// Generated fields are cloned whatever their type is
+#[allow(clippy::clone_on_copy)]
+mod models {
+    include!(concat!(env!("OUT_DIR"), "/models/mod.rs"));
+}
+pub use models::*;
+
+/// Rows passed to the `after_*` hooks of orders, e.g. `insert 1`
+pub static ORDER_EVENTS: Mutex<Vec<String>> = Mutex::new(vec![]);
+
+fn record(event: &str, row: &Orders) {
+    ORDER_EVENTS.lock().unwrap().push(format!("{} {}", event, row.id));
+}
+
+fn check_amount(order: &ActiveOrders) -> anyhow::Result<()> {
+    match &order.amount {
+        Set(amount) if *amount < 0 => anyhow::bail!("amount must not be negative"),
+        _ => Ok(()),
+    }
+}
+
+impl<DB> ModelHooks<DB> for ActiveOrders
+where
+    DB: OrmDB,
+    ActiveOrders: ModelOps<DB, NonActive = Orders>,
+    Orders: for<'r> sqlx::FromRow<'r, <DB as sqlx::Database>::Row>,
+{
+    async fn before_insert(&mut self, _conn: &mut <DB as sqlx::Database>::Connection) -> anyhow::Result<()> {
+        check_amount(self)?;
+        if self.order.is_none() {
+            self.order = Set(Some("new".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn after_insert(row: &Orders, _conn: &mut <DB as sqlx::Database>::Connection) -> anyhow::Result<()> {
+        record("insert", row);
+        Ok(())
+    }
+
+    async fn before_update(&mut self, _conn: &mut <DB as sqlx::Database>::Connection) -> anyhow::Result<()> {
+        check_amount(self)
+    }
+
+    async fn after_delete(row: &Orders, _conn: &mut <DB as sqlx::Database>::Connection) -> anyhow::Result<()> {
+        record("delete", row);
+        Ok(())
+    }
+}
+
+/// Empty in-memory database with the tables of `schema/`
+#[cfg(feature = "testing")]
+pub async fn sqlite_orm() -> anyhow::Result<Orm<sqlx::Pool<sqlx::Sqlite>>> {
+    orm::testing::sqlite_orm_from_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/schema")).await
+}
@@ -0,0 +1,62 @@
+use orm::prelude::*;
+use orm_tests::*;
+
+fn order() -> Orders {
+    Orders {
+        id: OrdersId(1),
+        user_id: UsersId(Default::default()),
+        amount: 1,
+        order: None,
+        version: 3,
+        created_at: Default::default(),
+        updated_at: Default::default(),
+    }
+}
+
+#[test]
+fn test_original_and_reset() {
+    let mut active = order().into_active();
+    assert_eq!(active.original().map(|o| o.amount), Some(1));
+    active.amount = Set(2);
+    assert!(active.is_dirty());
+    assert!(active.reset("amount"));
+    assert_eq!(active.amount.as_option(), Some(&1));
+    assert!(!active.is_dirty());
+    assert!(!active.reset("missing"));
+
+    let mut new = OrdersFactory::build().active();
+    assert!(new.original().is_none());
+    assert!(new.reset("amount"));
+    assert!(new.amount.is_none());
+}
+
+#[test]
+fn test_unknown_fields() {
+    let active = order().into_active();
+    assert!(active.is_field_set("amount"));
+    assert!(!active.is_field_set("missing"));
+    assert!(!active.is_field_dirty("missing"));
+}
+
+#[test]
+fn test_changed_only_and_detach() {
+    let mut active = order().into_active();
+    active.order = Set(Some("changed".to_string()));
+    active.created_at = Set(chrono::NaiveDateTime::MAX);
+    let changes = active.changes();
+    assert_eq!(changes.iter().map(|c| c.field).collect::<Vec<_>>(), vec!["order", "created_at"]);
+    assert_eq!(changes[0].old.as_deref(), Some("None"));
+
+    let changed = active.changed_only();
+    assert!(changed.original().is_none());
+    assert_eq!(changed.id.as_option(), Some(&OrdersId(1)));
+    assert_eq!(changed.version.as_option(), Some(&3));
+    assert!(changed.order.is_set());
+    assert!(changed.amount.is_none());
+    assert!(changed.created_at.is_none());
+
+    let original = active.detach().unwrap();
+    assert_eq!(original.version, 3);
+    assert!(active.original().is_none());
+    assert!(active.is_field_dirty("amount"));
+}
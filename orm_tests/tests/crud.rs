@@ -0,0 +1,233 @@
+use orm::prelude::*;
+use orm_tests::*;
+
+#[tokio::test]
+async fn test_factory_insert_and_select() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let user = UsersFactory::build().with_email("a@b.c").insert(orm.get_executor()).await?;
+    assert_eq!(user.email, "a@b.c");
+    assert_eq!(user.name, None);
+
+    let selected = orm.users().select_by_pk(&user.id).await?.unwrap();
+    assert_eq!((selected.id, selected.email), (user.id.clone(), user.email.clone()));
+    assert_eq!(orm.users().count().await?, 1);
+
+    let other = UsersFactory::build().insert(orm.get_executor()).await?;
+    assert_ne!(other.id, user.id);
+    assert_ne!(other.email, user.email);
+    assert_eq!(orm.users().count().await?, 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_insert_conflict() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let user = UsersFactory::build().active();
+    assert!(orm.users().save(user.clone(), Insert).await?.is_some());
+    assert!(orm.users().save(user, Insert).await?.is_none());
+    assert_eq!(orm.users().count().await?, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_update_changed_fields() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let user = UsersFactory::build().insert(orm.get_executor()).await?;
+    let mut active = user.clone().into_active();
+    active.name = Set(Some("Name".to_string()));
+    let updated = orm.users().save(active, Update).await?.unwrap();
+    assert_eq!(updated.name.as_deref(), Some("Name"));
+    assert_eq!(updated.email, user.email);
+
+    let missing = UsersFactory::build().active();
+    assert!(orm.users().save(missing, Update).await?.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_upsert() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let user = UsersFactory::build().insert(orm.get_executor()).await?;
+    let active = UsersFactory::build().with_id(user.id.clone()).with_email("new@b.c").active();
+    let upserted = orm.users().save(active, Upsert).await?.unwrap();
+    assert_eq!(upserted.id, user.id);
+    assert_eq!(upserted.email, "new@b.c");
+    assert_eq!(orm.users().count().await?, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reference_shares_newtype() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let user = UsersFactory::build().insert(orm.get_executor()).await?;
+    let order = OrdersFactory::build().with_user_id(user.id.clone()).insert(orm.get_executor()).await?;
+    let user_id: &UsersId = &order.user_id;
+    assert_eq!(user_id, &user.id);
+
+    let selected = orm.users().select_by_pk(&order.user_id).await?.unwrap();
+    assert_eq!(selected.id, user.id);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_raw_queries() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let user = UsersFactory::build().with_name("Named".to_string()).insert(orm.get_executor()).await?;
+    UsersFactory::build().insert(orm.get_executor()).await?;
+
+    let found = orm.users().select("where email = ?").bind(user.email.clone()).fetch().await?;
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id.as_option(), Some(&user.id));
+
+    let named: Vec<Users> = orm.users().select_as("where name is not null").fetch().await?;
+    assert_eq!(named.len(), 1);
+    assert_eq!(named[0].name.as_deref(), Some("Named"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_version_bump_and_stale_update() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let mut new = OrdersFactory::build().active();
+    new.version = NotSet;
+    let order = orm.orders().save(new, Insert).await?.unwrap();
+    assert_eq!(order.version, 1);
+
+    let mut active = order.clone().into_active();
+    active.amount = Set(5);
+    let updated = orm.orders().save(active, Update).await?.unwrap();
+    assert_eq!((updated.amount, updated.version), (5, 2));
+
+    let mut stale = order.into_active();
+    stale.amount = Set(6);
+    let err = orm.orders().save(stale, Update).await.unwrap_err();
+    assert!(matches!(err.downcast_ref::<OrmError>(), Some(OrmError::StaleObject)));
+    let current = orm.orders().select_by_pk(&updated.id).await?.unwrap();
+    assert_eq!((current.amount, current.version), (5, 2));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_soft_delete_restore_and_force_delete() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let user = UsersFactory::build().insert(orm.get_executor()).await?;
+    let alive = UsersFactory::build().insert(orm.get_executor()).await?;
+
+    let deleted = orm.users().delete_by_pk(&user.id).await?.unwrap();
+    assert!(deleted.deleted_at.is_some());
+    assert!(orm.users().delete_by_pk(&user.id).await?.is_none());
+    assert!(orm.users().select_by_pk(&user.id).await?.is_none());
+    assert!(orm.users().with_deleted().select_by_pk(&user.id).await?.is_some());
+    assert_eq!(orm.users().count().await?, 1);
+    assert_eq!(orm.users().with_deleted().count().await?, 2);
+    assert_eq!(orm.users().only_deleted().count().await?, 1);
+    let only_deleted = orm.users().only_deleted().select("").fetch().await?;
+    assert_eq!(only_deleted.len(), 1);
+    assert_eq!(only_deleted[0].id.as_option(), Some(&user.id));
+
+    let restored = orm.users().restore(&user.id).await?.unwrap();
+    assert_eq!(restored.deleted_at, None);
+    assert!(orm.users().restore(&alive.id).await?.is_none());
+    assert_eq!(orm.users().count().await?, 2);
+
+    assert!(orm.users().force_delete(&user.id).await?.is_some());
+    assert!(orm.users().with_deleted().select_by_pk(&user.id).await?.is_none());
+    assert_eq!(orm.users().with_deleted().count().await?, 1);
+    assert!(orm.users().select_by_pk(&alive.id).await?.is_some());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_raw_soft_delete_skips_deleted_rows() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let user = UsersFactory::build().insert(orm.get_executor()).await?;
+    let other = UsersFactory::build().insert(orm.get_executor()).await?;
+
+    let deleted = orm.users().delete("where email = ? returning *").bind(user.email.clone()).fetch().await?;
+    assert_eq!(deleted.len(), 1);
+    let deleted_at = deleted[0].deleted_at.clone();
+    assert!(deleted_at.as_option().is_some_and(Option::is_some));
+
+    let again = orm.users().delete("where email = ? or email = ? returning *")
+        .bind(user.email.clone())
+        .bind(user.email.clone())
+        .fetch().await?;
+    assert!(again.is_empty());
+    let kept = orm.users().with_deleted().select_by_pk(&user.id).await?.unwrap();
+    assert_eq!(Some(kept.deleted_at), deleted_at.into_option());
+
+    let rest = orm.users().delete("returning *").fetch().await?;
+    assert_eq!(rest.len(), 1);
+    assert_eq!(rest[0].id.as_option(), Some(&other.id));
+    assert_eq!(orm.users().count().await?, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_full_statements_keep_soft_deleted_rows() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let user = UsersFactory::build().insert(orm.get_executor()).await?;
+    UsersFactory::build().insert(orm.get_executor()).await?;
+    orm.users().delete_by_pk(&user.id).await?;
+
+    let err = orm.users().delete(r#"delete from "users" returning *"#).fetch().await.unwrap_err();
+    assert!(matches!(err.downcast_ref(), Some(OrmError::UnscopedStatement("delete"))));
+    assert!(orm.users().select(r#"select * from "users""#).fetch().await.is_err());
+    assert!(orm.users().only_deleted().select(r#"select * from "users""#).fetch().await.is_err());
+    assert_eq!(orm.users().with_deleted().select(r#"select * from "users""#).fetch().await?.len(), 2);
+
+    let orders = orm.orders().delete(r#"delete from "orders" returning *"#).fetch().await?;
+    assert!(orders.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_auto_timestamps() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let before = chrono::Utc::now().naive_utc() - chrono::Duration::seconds(1);
+    let order = OrdersFactory::build().insert(orm.get_executor()).await?;
+    assert!(order.created_at >= before);
+    assert_eq!(order.updated_at, order.created_at);
+
+    // CURRENT_TIMESTAMP of SQLite has a precision of one second
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    let mut active = order.clone().into_active();
+    active.amount = Set(7);
+    let updated = orm.orders().save(active, Update).await?.unwrap();
+    assert_eq!(updated.created_at, order.created_at);
+    assert!(updated.updated_at > order.updated_at);
+
+    let selected = orm.orders().select_by_pk(&order.id).await?.unwrap();
+    assert_eq!((selected.created_at, selected.updated_at), (updated.created_at, updated.updated_at));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_written() -> anyhow::Result<()> {
+    type Lite = sqlx::Sqlite;
+    let orm = sqlite_orm().await?;
+    let mut conn = orm.get_executor().acquire().await?;
+
+    let new = OrdersFactory::build().with_amount(4).active();
+    let sql = <ActiveOrders as SqlBuilder<Lite>>::insert_for(&new)?;
+    new.complete_plain_query(sqlx::query(&sql)).execute(&mut *conn).await?;
+    let inserted = <ActiveOrders as WrittenRows<Lite>>::select_written(&new, &mut conn).await?.unwrap();
+    assert_eq!(Some(&inserted.id), new.id.as_option());
+    assert_eq!((inserted.amount, inserted.version), (4, 1));
+
+    let changed = OrdersFactory::build().with_id(inserted.id.clone()).with_amount(9).active();
+    let sql = <ActiveOrders as SqlBuilder<Lite>>::upsert_for(&changed)?;
+    changed.complete_plain_query(sqlx::query(&sql)).execute(&mut *conn).await?;
+    let upserted = <ActiveOrders as WrittenRows<Lite>>::select_written(&changed, &mut conn).await?.unwrap();
+    assert_eq!((&upserted.id, upserted.amount), (&inserted.id, 9));
+
+    // Without a primary key the row is found by the key SQLite generated for it
+    sqlx::query(r#"INSERT INTO "orders" ("user_id", "amount", "version", "created_at", "updated_at") VALUES (?, 11, 1, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)"#)
+        .bind(inserted.user_id)
+        .execute(&mut *conn)
+        .await?;
+    let generated = <ActiveOrders as WrittenRows<Lite>>::select_written(&ActiveOrders::default(), &mut conn).await?.unwrap();
+    assert_eq!(generated.amount, 11);
+    assert_ne!(generated.id, upserted.id);
+    Ok(())
+}
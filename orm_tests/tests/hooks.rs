@@ -0,0 +1,70 @@
+use orm::prelude::*;
+use orm_tests::*;
+
+fn events_of(row: &Orders) -> Vec<String> {
+    let suffix = format!(" {}", row.id);
+    ORDER_EVENTS.lock().unwrap().iter().filter(|e| e.ends_with(&suffix)).cloned().collect()
+}
+
+/// Generic over the database and the executor with a single bound
+async fn create<'e, DB, E>(order: ActiveOrders, exec: E) -> anyhow::Result<Option<Orders>>
+where
+    DB: OrmDB,
+    E: OrmAcquire<'e, DB>,
+    ActiveOrders: ModelOps<DB, NonActive = Orders>,
+    Orders: for<'r> sqlx::FromRow<'r, <DB as sqlx::Database>::Row>,
+    for<'q> <DB as sqlx::Database>::Arguments<'q>: Default + sqlx::IntoArguments<'q, DB>,
+{
+    order.insert(exec).await
+}
+
+#[tokio::test]
+async fn test_hooks_run_around_writes() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let order = OrdersFactory::build().insert(orm.get_executor()).await?;
+    assert_eq!(order.order.as_deref(), Some("new"));
+    assert_eq!(events_of(&order), vec![format!("insert {}", order.id)]);
+
+    orm.orders().delete_by_pk(&order.id).await?.unwrap();
+    assert_eq!(events_of(&order), vec![format!("insert {}", order.id), format!("delete {}", order.id)]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hook_error_aborts_write() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let negative = OrdersFactory::build().with_amount(-1).active();
+    let err = orm.orders().save(negative.clone(), Insert).await.unwrap_err();
+    assert_eq!(err.to_string(), "amount must not be negative");
+    assert!(orm.orders().save(negative, Upsert).await.is_err());
+    assert_eq!(orm.orders().count().await?, 0);
+
+    let order = OrdersFactory::build().with_amount(3).insert(orm.get_executor()).await?;
+    let mut active = order.clone().into_active();
+    active.amount = Set(-5);
+    assert!(orm.orders().save(active, Update).await.is_err());
+    let stored = orm.orders().select_by_pk(&order.id).await?.unwrap();
+    assert_eq!((stored.amount, stored.version), (3, 1));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_accepted_executors() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let pool = orm.get_executor();
+    assert!(create(OrdersFactory::build().active(), pool).await?.is_some());
+
+    let mut conn = pool.acquire().await?;
+    assert!(create(OrdersFactory::build().active(), &mut conn).await?.is_some());
+    assert!(create(OrdersFactory::build().active(), &mut *conn).await?.is_some());
+    drop(conn);
+
+    let mut tx = pool.begin().await?;
+    let in_tx = create(OrdersFactory::build().active(), &mut tx).await?.unwrap();
+    assert!(create(OrdersFactory::build().active(), &mut *tx).await?.is_some());
+    tx.rollback().await?;
+    assert!(orm.orders().select_by_pk(&in_tx.id).await?.is_none());
+    assert_eq!(events_of(&in_tx), vec![format!("insert {}", in_tx.id)]);
+    assert_eq!(orm.orders().count().await?, 3);
+    Ok(())
+}
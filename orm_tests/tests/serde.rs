@@ -0,0 +1,47 @@
+#![cfg(feature = "serde")]
+
+use orm::prelude::*;
+use orm_tests::*;
+use serde_json::json;
+
+#[test]
+fn test_absent_and_null_fields() {
+    let active: ActiveUsers = serde_json::from_value(json!({"email": "a@b.c", "name": null})).unwrap();
+    assert_eq!(active.email.as_option().map(String::as_str), Some("a@b.c"));
+    assert!(matches!(active.name, Set(None)));
+    assert!(active.id.is_none());
+    assert!(active.deleted_at.is_none());
+
+    let named: ActiveUsers = serde_json::from_value(json!({"name": "Name"})).unwrap();
+    assert!(matches!(&named.name, Set(Some(name)) if name == "Name"));
+    assert!(named.email.is_none());
+}
+
+#[test]
+fn test_round_trip() {
+    let active: ActiveUsers = serde_json::from_value(json!({"email": "a@b.c", "name": null})).unwrap();
+    let value = serde_json::to_value(&active).unwrap();
+    assert_eq!(value, json!({"email": "a@b.c", "name": null}));
+
+    let again: ActiveUsers = serde_json::from_value(value).unwrap();
+    assert!(matches!(again.name, Set(None)));
+    assert!(again.id.is_none());
+    assert_eq!(serde_json::to_value(ActiveUsers::default()).unwrap(), json!({}));
+}
+
+#[test]
+fn test_loaded_model_round_trip() {
+    let user = Users {
+        id: orm::testing::Fake::fake(1),
+        email: "a@b.c".to_string(),
+        name: None,
+        deleted_at: None,
+    };
+    let value = serde_json::to_value(user.clone().into_active()).unwrap();
+    assert_eq!(value["name"], serde_json::Value::Null);
+    assert_eq!(value["deleted_at"], serde_json::Value::Null);
+
+    let active: ActiveUsers = serde_json::from_value(value).unwrap();
+    assert!(active.original().is_none());
+    assert_eq!(active.into_users().map(|u| (u.id, u.email)), Some((user.id, user.email)));
+}
@@ -0,0 +1,178 @@
+#[cfg(feature = "postgres")]
+use std::any::type_name;
+
+use orm::prelude::*;
+use orm_tests::*;
+
+#[cfg(feature = "postgres")]
+type Pg = sqlx::Postgres;
+#[cfg(feature = "mysql")]
+type My = sqlx::MySql;
+#[cfg(feature = "sqlite")]
+type Lite = sqlx::Sqlite;
+
+#[cfg(feature = "postgres")]
+fn order() -> Orders {
+    Orders {
+        id: OrdersId(1),
+        user_id: UsersId(Default::default()),
+        amount: 1,
+        order: None,
+        version: 3,
+        created_at: Default::default(),
+        updated_at: Default::default(),
+    }
+}
+
+#[test]
+fn test_insert_per_dialect() {
+    let new = OrdersFactory::build().with_id(OrdersId(1)).active();
+    #[cfg(feature = "postgres")]
+    assert_eq!(
+        <ActiveOrders as SqlBuilder<Pg>>::insert_for(&new).unwrap(),
+        r#"INSERT INTO "public"."orders" ("id", "user_id", "version", "created_at", "updated_at") VALUES ($1, $2, $3, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP) RETURNING *"#
+    );
+    #[cfg(feature = "mysql")]
+    assert_eq!(
+        <ActiveOrders as SqlBuilder<My>>::insert_for(&new).unwrap(),
+        "INSERT INTO `public`.`orders` (`id`, `user_id`, `version`, `created_at`, `updated_at`) VALUES (?, ?, ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)"
+    );
+    #[cfg(feature = "sqlite")]
+    assert_eq!(
+        <ActiveOrders as SqlBuilder<Lite>>::insert_for(&new).unwrap(),
+        r#"INSERT INTO "orders" ("id", "user_id", "version", "created_at", "updated_at") VALUES (?, ?, ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP) RETURNING *"#
+    );
+    #[cfg(feature = "mysql")]
+    assert_eq!(
+        <ActiveOrders as SqlBuilder<My>>::upsert_for(&new).unwrap(),
+        "INSERT INTO `public`.`orders` (`id`, `user_id`, `version`, `created_at`, `updated_at`) VALUES (?, ?, ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP) \
+         ON DUPLICATE KEY UPDATE `id` = VALUES(`id`), `user_id` = VALUES(`user_id`), `version` = VALUES(`version`), `updated_at` = VALUES(`updated_at`)"
+    );
+    #[cfg(feature = "mysql")]
+    assert_eq!(
+        <ActiveOrders as SqlBuilder<My>>::select_last_inserted().unwrap(),
+        "SELECT `id`, `user_id`, `amount`, `order`, `version`, `created_at`, `updated_at` FROM `public`.`orders` WHERE `id` = LAST_INSERT_ID()"
+    );
+    #[cfg(feature = "postgres")]
+    assert!(<ActiveOrders as SqlBuilder<Pg>>::select_last_inserted().is_err());
+}
+
+#[test]
+#[cfg(feature = "postgres")]
+fn test_insert_initial_version() {
+    let mut new = OrdersFactory::build().with_id(OrdersId(1)).active();
+    assert_eq!(new.version.as_option(), Some(&1));
+    new.version = NotSet;
+    assert_eq!(
+        <ActiveOrders as SqlBuilder<Pg>>::insert_for(&new).unwrap(),
+        r#"INSERT INTO "public"."orders" ("id", "user_id", "version", "created_at", "updated_at") VALUES ($1, $2, 1, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP) RETURNING *"#
+    );
+    assert_eq!(
+        <ActiveOrders as SqlBuilder<Pg>>::upsert_for(&new).unwrap(),
+        r#"INSERT INTO "public"."orders" ("id", "user_id", "version", "created_at", "updated_at") VALUES ($1, $2, 1, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP) ON CONFLICT ("id") DO UPDATE SET "id" = EXCLUDED."id", "user_id" = EXCLUDED."user_id", "updated_at" = EXCLUDED."updated_at" RETURNING *"#
+    );
+}
+
+#[test]
+#[cfg(feature = "postgres")]
+fn test_update_checks_version() {
+    let mut active = order().into_active();
+    active.amount = Set(2);
+    assert_eq!(
+        <ActiveOrders as SqlBuilder<Pg>>::update_for(&active.changed_only()).unwrap(),
+        r#"UPDATE "public"."orders" SET "amount" = $1, "updated_at" = CURRENT_TIMESTAMP, "version" = "version" + 1 WHERE "id" = $2 AND "version" = $3 RETURNING *"#
+    );
+
+    let mut untouched = order().into_active();
+    untouched.created_at = Set(chrono::NaiveDateTime::MAX);
+    assert!(matches!(
+        <ActiveOrders as SqlBuilder<Pg>>::update_for(&untouched.changed_only()),
+        Err(OrmError::NothingToUpdate)
+    ));
+}
+
+#[test]
+fn test_soft_delete_scopes() {
+    type U = ActiveUsers;
+    #[cfg(feature = "postgres")]
+    assert_eq!(
+        <U as SqlBuilder<Pg>>::delete_by_pk(),
+        r#"UPDATE "public"."users" SET "deleted_at" = CURRENT_TIMESTAMP WHERE "id" = $1 AND "deleted_at" IS NULL RETURNING *"#
+    );
+    #[cfg(feature = "postgres")]
+    assert_eq!(
+        <U as SqlBuilder<Pg>>::select_by_pk(),
+        r#"SELECT "id", "email", "name", "deleted_at" FROM "public"."users" WHERE "id" = $1 AND "deleted_at" IS NULL"#
+    );
+    #[cfg(feature = "sqlite")]
+    assert_eq!(<U as SqlBuilder<Lite>>::count(), r#"SELECT COUNT(*) as cnt FROM "users" WHERE "deleted_at" IS NULL"#);
+    #[cfg(feature = "mysql")]
+    assert_eq!(
+        <U as SqlBuilder<My>>::count_scoped(DeletedScope::OnlyDeleted),
+        "SELECT COUNT(*) as cnt FROM `public`.`users` WHERE `deleted_at` IS NOT NULL"
+    );
+    #[cfg(feature = "postgres")]
+    {
+        assert_eq!(<U as SqlBuilder<Pg>>::count_scoped(DeletedScope::WithDeleted), r#"SELECT COUNT(*) as cnt FROM "public"."users""#);
+        assert_eq!(
+            <U as SqlBuilder<Pg>>::restore_by_pk().unwrap(),
+            r#"UPDATE "public"."users" SET "deleted_at" = NULL WHERE "id" = $1 AND "deleted_at" IS NOT NULL RETURNING *"#
+        );
+        assert!(<ActiveOrders as SqlBuilder<Pg>>::restore_by_pk().is_err());
+        assert!(<ActiveOrders as SqlBuilder<Pg>>::delete_by_pk().starts_with("DELETE"));
+    }
+}
+
+/// Types of the bound values in bind order
+#[cfg(feature = "postgres")]
+#[derive(Default)]
+struct BoundTypes(Vec<&'static str>);
+
+#[cfg(feature = "postgres")]
+impl<'q> BindQuery<'q, Pg> for BoundTypes {
+    fn bind_value<V: 'q + sqlx::Encode<'q, Pg> + sqlx::Type<Pg>>(mut self, _value: V) -> Self {
+        self.0.push(type_name::<V>());
+        self
+    }
+}
+
+#[test]
+#[cfg(feature = "postgres")]
+fn test_bind_order() {
+    let new = OrdersFactory::build().with_id(OrdersId(1)).active();
+    let bound = <ActiveOrders as BindValues<Pg>>::bind_values(&new, BoundTypes::default());
+    assert_eq!(bound.0, vec![type_name::<&OrdersId>(), type_name::<&UsersId>(), type_name::<&i64>()]);
+
+    let mut active = order().into_active();
+    active.order = Set(Some("changed".to_string()));
+    let changed = active.changed_only();
+    let columns = <ActiveOrders as SqlBuilder<Pg>>::update_bind_order(&changed).unwrap();
+    assert_eq!(columns, vec!["order", "id", "version"]);
+    let (sql, order) = <ActiveOrders as SqlBuilder<Pg>>::update_with_bind_order(&changed).unwrap();
+    assert_eq!((sql, order), (<ActiveOrders as SqlBuilder<Pg>>::update_for(&changed).unwrap(), columns.clone()));
+    let bound = <ActiveOrders as BindValues<Pg>>::bind_columns(&changed, BoundTypes::default(), &columns);
+    assert_eq!(bound.0, vec![type_name::<&Option<String>>(), type_name::<&OrdersId>(), type_name::<&i64>()]);
+
+    let bound = <ActiveOrders as BindValues<Pg>>::bind_columns(&changed, BoundTypes::default(), &["amount", "missing"]);
+    assert!(bound.0.is_empty());
+}
+
+#[test]
+fn test_isolation_statements() {
+    let level = IsolationLevel::Serializable;
+    #[cfg(feature = "postgres")]
+    {
+        assert_eq!(<Pg as SqlGen>::begin_statement(level).as_deref(), Some("BEGIN ISOLATION LEVEL SERIALIZABLE"));
+        assert_eq!(<Pg as SqlGen>::isolation_statement(level), None);
+    }
+    #[cfg(feature = "mysql")]
+    {
+        assert_eq!(<My as SqlGen>::begin_statement(level), None);
+        assert_eq!(<My as SqlGen>::isolation_statement(level).as_deref(), Some("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE"));
+    }
+    #[cfg(feature = "sqlite")]
+    {
+        assert_eq!(<Lite as SqlGen>::begin_statement(level), None);
+        assert_eq!(<Lite as SqlGen>::isolation_statement(level), None);
+    }
+}
@@ -0,0 +1,137 @@
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+use orm::prelude::*;
+use orm_tests::*;
+
+/// `SQLITE_BUSY`, retryable for SQLite
+#[derive(Debug)]
+struct Busy;
+
+impl std::fmt::Display for Busy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "database is locked")
+    }
+}
+
+impl std::error::Error for Busy {}
+
+impl sqlx::error::DatabaseError for Busy {
+    fn message(&self) -> &str {
+        "database is locked"
+    }
+    fn code(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed("5"))
+    }
+    fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        self
+    }
+    fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+        self
+    }
+    fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+        self
+    }
+    fn kind(&self) -> sqlx::error::ErrorKind {
+        sqlx::error::ErrorKind::Other
+    }
+}
+
+fn busy() -> anyhow::Error {
+    sqlx::Error::Database(Box::new(Busy)).into()
+}
+
+fn no_wait(max_attempts: u32) -> TxOptions {
+    TxOptions::default().retry(RetryPolicy { max_attempts, backoff: Duration::ZERO, max_backoff: Duration::ZERO })
+}
+
+#[tokio::test]
+async fn test_commit_on_ok() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let user = UsersFactory::build().active();
+    let inserted = orm.transaction(|tx| {
+        let user = user.clone();
+        Box::pin(async move { tx.users().save(user, Insert).await })
+    }).await?.unwrap();
+    assert!(orm.users().select_by_pk(&inserted.id).await?.is_some());
+
+    let options = TxOptions::default().isolation(IsolationLevel::Serializable);
+    let user = UsersFactory::build().active();
+    orm.transaction_with(options, |tx| {
+        let user = user.clone();
+        Box::pin(async move { tx.users().save(user, Insert).await })
+    }).await?;
+    assert_eq!(orm.users().count().await?, 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rollback_on_err() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let user = UsersFactory::build().active();
+    let result: anyhow::Result<()> = orm.transaction(|tx| {
+        let user = user.clone();
+        Box::pin(async move {
+            tx.users().save(user, Insert).await?;
+            anyhow::bail!("failed after insert")
+        })
+    }).await;
+    assert_eq!(result.unwrap_err().to_string(), "failed after insert");
+    assert_eq!(orm.users().count().await?, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_savepoint_rollback() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let kept = UsersFactory::build().active();
+    let dropped = UsersFactory::build().active();
+    let mut tx = orm.begin_tx().await?;
+    tx.users().save(kept.clone(), Insert).await?;
+    let mut savepoint = tx.savepoint().await?;
+    savepoint.users().save(dropped.clone(), Insert).await?;
+    savepoint.rollback().await?;
+    tx.commit().await?;
+
+    assert!(orm.users().select_by_pk(kept.id.as_option().unwrap()).await?.is_some());
+    assert!(orm.users().select_by_pk(dropped.id.as_option().unwrap()).await?.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_retries_retryable_errors() -> anyhow::Result<()> {
+    let orm = sqlite_orm().await?;
+    let attempts = AtomicU32::new(0);
+    let result = orm.transaction_with(no_wait(3), |_| {
+        let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+        Box::pin(async move { if attempt < 3 { Err(busy()) } else { Ok(attempt) } })
+    }).await?;
+    assert_eq!(result, 3);
+
+    attempts.store(0, Ordering::SeqCst);
+    let result: anyhow::Result<()> = orm.transaction_with(no_wait(2), |_| {
+        attempts.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async { Err(busy()) })
+    }).await;
+    assert!(result.is_err());
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+    attempts.store(0, Ordering::SeqCst);
+    let result: anyhow::Result<()> = orm.transaction_with(no_wait(3), |_| {
+        attempts.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async { anyhow::bail!("not retryable") })
+    }).await;
+    assert!(result.is_err());
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+fn test_retry_delay_is_capped() {
+    let policy = RetryPolicy { max_attempts: 10, backoff: Duration::from_millis(10), max_backoff: Duration::from_millis(50) };
+    let delays: Vec<u128> = (1..=5).map(|attempt| policy.delay(attempt).as_millis()).collect();
+    assert_eq!(delays, vec![10, 20, 40, 50, 50]);
+    assert_eq!(policy.delay(u32::MAX), Duration::from_millis(50));
+    assert_eq!(RetryPolicy::default().delay(100), RetryPolicy::default().max_backoff);
+}
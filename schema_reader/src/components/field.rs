@@ -16,7 +16,32 @@ pub struct Field {
     #[serde(rename = "nullable", skip_serializing_if = "Option::is_none")]
     pub nullable: Option<bool>,
     #[serde(rename = "isUnique")]
-    pub is_unique: Option<bool>
+    pub is_unique: Option<bool>,
+    /// Generate a newtype `{Table}{Field}` for this field, defaults to `newtype` of the type for primary keys
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newtype: Option<bool>,
+    /// Optimistic locking counter, checked and incremented on every update
+    #[serde(rename = "version")]
+    #[serde(default)]
+    pub is_version: bool,
+    /// Set to the current time on insert, never changed by updates
+    #[serde(rename = "autoCreate")]
+    #[serde(default)]
+    pub auto_create: bool,
+    /// Set to the current time on every insert and update
+    #[serde(rename = "autoUpdate")]
+    #[serde(default)]
+    pub auto_update: bool,
+    /// Newtype primary key this field points to as `table.field`, the field gets its type, e.g. `UsersId`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub references: Option<String>,
+}
+
+/// Field of another table whose newtype is reused
+#[derive(Clone, Serialize, Deserialize, Default, Debug, PartialEq, Hash)]
+pub struct Reference {
+    pub table: String,
+    pub field: String,
 }
 
 #[derive(Clone, Serialize, Deserialize, Default, Debug, PartialEq, Hash)]
@@ -28,6 +53,11 @@ pub struct TypedField {
     pub default: Option<String>,
     pub nullable: bool,
     pub is_unique: bool,
+    pub newtype: bool,
+    pub is_version: bool,
+    pub auto_create: bool,
+    pub auto_update: bool,
+    pub references: Option<Reference>,
 }
 
 impl Field {
@@ -43,14 +73,29 @@ impl Field {
                 is_unique: self.is_unique.unwrap_or(false),
                 default: self.default.clone(),
                 nullable: self.nullable.unwrap_or(false),
+                newtype: self.newtype.unwrap_or(self.is_primary && field_type.newtype),
+                is_version: self.is_version,
+                auto_create: self.auto_create,
+                auto_update: self.auto_update,
+                references: self.references.and_then(|r| {
+                    let (table, field) = r.split_once('.')?;
+                    Some(Reference { table: table.to_string(), field: field.to_string() })
+                }),
             }
         )
     }
 }
 
 impl TypedField {
+    /// Newtypes derive `Eq`, `Hash`, `Ord`, `Display` and `FromStr`, which floats, decimals and json values miss
+    pub fn newtype_supported(&self) -> bool {
+        let path = self.type_str.split('<').next().unwrap_or_default();
+        let name = path.rsplit("::").next().unwrap_or_default().trim();
+        !matches!(name, "f32" | "f64" | "Decimal" | "BigDecimal" | "Value" | "JsonValue" | "Json")
+    }
+
     pub fn map_type(&mut self, target: &TypeMapping, mappings: &HashMap<String, Type>) -> anyhow::Result<()> {
-        self.type_str = mappings.get(&self.type_name).or_err::<anyhow::Error>("Unknown type")?.get_mapping(&target).to_string();
+        self.type_str = mappings.get(&self.type_name).or_err::<anyhow::Error>("Unknown type")?.get_mapping(target).to_string();
         Ok(())
     }
 }
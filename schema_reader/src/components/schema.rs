@@ -52,9 +52,33 @@ impl RawYamlSchema {
             }
             flatten_tables.insert(table_name.clone(), table.complete(&self.types).map_err(|e: String| anyhow::anyhow!(e))?);
         }
+        Self::check_references(&flatten_tables)?;
         Ok(Schema { tables: flatten_tables, types: self.types, type_mapping: TypeMapping::Rust })
     }
 
+    /// Referenced fields must be newtypes of the same type
+    fn check_references(tables: &HashMap<String, Table>) -> Result<()> {
+        for table in tables.values() {
+            for field in table.fields.iter() {
+                let Some(reference) = &field.references else { continue };
+                let target = tables.get(&reference.table)
+                    .and_then(|t| t.fields.iter().find(|f| f.name == reference.field));
+                match target {
+                    Some(target) if !target.newtype => anyhow::bail!(
+                        "Field {}.{} references {}.{} which is not a newtype", table.name, field.name, reference.table, reference.field
+                    ),
+                    Some(target) if target.type_name != field.type_name => anyhow::bail!(
+                        "Field {}.{} of type {} references {}.{} of type {}",
+                        table.name, field.name, field.type_name, reference.table, reference.field, target.type_name
+                    ),
+                    Some(_) => {}
+                    None => anyhow::bail!("Field {}.{} references unknown field {}.{}", table.name, field.name, reference.table, reference.field),
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn from_dir<P: AsRef<Path>>(path: P) -> Result<Self> {
         let mut result = Self::default();
         for entry in fs::read_dir(&path)? {
@@ -94,19 +118,25 @@ pub enum TypeMapping {
     #[default]
     Rust,
     Pg,
+    Sqlite,
 }
 
 impl Schema {
+    /// Already flattened schema, e.g. restored from a saved migration state
+    pub fn new(tables: HashMap<String, Table>, types: HashMap<String, Type>, type_mapping: TypeMapping) -> Self {
+        Self { tables, types, type_mapping }
+    }
+
     pub fn get_tables(&self) -> &HashMap<String, Table> {
         &self.tables
     } 
 
     pub fn from_dir<P: AsRef<Path>>(path: P) -> Result<Self> {
-        Ok(RawYamlSchema::from_dir(path)?.flatten()?)
+        RawYamlSchema::from_dir(path)?.flatten()
     }
 
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        Ok(RawYamlSchema::from_file(path)?.flatten()?)
+        RawYamlSchema::from_file(path)?.flatten()
     }
 
     pub fn extend(&mut self, schema: Self) {
@@ -164,7 +194,7 @@ impl Schema {
         for (key, value) in other.tables.iter() {
             if let Some(table) = removed.remove(key) {
                 let Option::Some(mut table) = table.difference(value) else {continue};
-                table.map_types(target, &types)?;
+                table.map_types(target, types)?;
                 changed.push(table);
             } else {
                 added.push(TableAdded(value.clone()));
@@ -14,6 +14,12 @@ pub struct RawTable {
     pub extends: Option<String>,
     pub schema: Option<String>,
     pub fields: Vec<Field>,
+    /// Nullable timestamp column set instead of deleting rows
+    #[serde(rename = "softDelete")]
+    pub soft_delete: Option<String>,
+    /// `ModelHooks` are implemented by hand instead of the generated no-op impl
+    #[serde(default)]
+    pub hooks: bool,
 }
 
 impl RawTable {
@@ -22,6 +28,9 @@ impl RawTable {
         if self.schema.is_none() {
             self.schema = other.schema;
         }
+        if self.soft_delete.is_none() {
+            self.soft_delete = other.soft_delete;
+        }
     }
 }
 
@@ -38,21 +47,42 @@ impl RawTable {
                 pks.push(field.name.clone());
             }
             let err_msg = format!("Unknown type {}", field.type_name);
-            let Some(typed_field) = field.into_typed(&crate::prelude::TypeMapping::default(), &types) else {
+            if field.references.as_ref().is_some_and(|r| !r.contains('.')) {
+                return Err(format!("Field {}.{} must reference a field as table.field", self.name, field.name));
+            }
+            let Some(typed_field) = field.into_typed(&crate::prelude::TypeMapping::default(), types) else {
                 return Err(err_msg);
             };
+            if typed_field.newtype && !typed_field.newtype_supported() {
+                return Err(format!(
+                    "Field {}.{} can't be a newtype, {} doesn't implement Eq, Hash, Ord, Display and FromStr",
+                    self.name, typed_field.name, typed_field.type_str
+                ));
+            }
+            if typed_field.newtype && typed_field.references.is_some() {
+                return Err(format!("Field {}.{} can't be a newtype and reference another field", self.name, typed_field.name));
+            }
             fields.push(typed_field);
         }
         if pks.len() > 1 {
             return Err(format!("Table {} has multiple primary keys: {}", self.name, pks.join(", ")));
         }
-        if pks.len() == 0 && !self.is_abstract {
+        if pks.is_empty() && !self.is_abstract {
             return Err(format!("Table {} has no primary key", self.name));
         }
+        if let Some(column) = &self.soft_delete {
+            match fields.iter().find(|f| &f.name == column) {
+                Some(f) if f.nullable => {}
+                Some(_) => return Err(format!("Soft delete column {}.{} must be nullable", self.name, column)),
+                None => return Err(format!("Table {} has no soft delete column {}", self.name, column)),
+            }
+        }
         Ok(Table {
             name: self.name,
             schema,
             fields,
+            soft_delete: self.soft_delete,
+            hooks: self.hooks,
         })
     }
 }
@@ -62,6 +92,8 @@ pub struct Table {
     pub name: String,
     pub schema: String,
     pub fields: Vec<TypedField>,
+    pub soft_delete: Option<String>,
+    pub hooks: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -131,7 +163,7 @@ impl ChangedField {
     }
 
     pub fn map_type(&mut self, target: &TypeMapping, mappings: &HashMap<String, Type>) -> anyhow::Result<()> {
-        self.type_str = mappings.get(&self.type_name).or_err::<anyhow::Error>("Unknown type")?.get_mapping(&target).to_string();
+        self.type_str = mappings.get(&self.type_name).or_err::<anyhow::Error>("Unknown type")?.get_mapping(target).to_string();
         Ok(())
     }
 }
@@ -164,13 +196,12 @@ impl Table {
 
         for field in other.fields.iter() {
             if let Some(kept) = removed.remove(&field.name) {
-                ChangedField::from_difference(&kept, field)
-                    .map(|v| changed.push(v));
+                if let Some(v) = ChangedField::from_difference(&kept, field) { changed.push(v) }
             } else {
                 added.push(field.clone());
             }
         }
-        changed.sort_by(|a, b| a.pk.cmp(&b.pk));
+        changed.sort_by_key(|a| a.pk);
 
         Some(TableChanged {
             name: self.name.clone(),
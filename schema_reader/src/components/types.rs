@@ -9,6 +9,13 @@ pub struct Type {
     pub rust_type: String,
     #[serde(rename = "pgType")]
     pub pg_type: String,
+    /// Column type for SQLite, defaults to `pgType` which SQLite accepts by type affinity
+    #[serde(rename = "sqliteType")]
+    #[serde(default)]
+    pub sqlite_type: Option<String>,
+    /// Primary keys of this type get a per-table newtype, e.g. `UsersId(Uuid)`
+    #[serde(default)]
+    pub newtype: bool,
 }
 
 impl Type {
@@ -16,6 +23,7 @@ impl Type {
         match target {
             TypeMapping::Rust => &self.rust_type,
             TypeMapping::Pg => &self.pg_type,
+            TypeMapping::Sqlite => self.sqlite_type.as_deref().unwrap_or(&self.pg_type),
         }
     }    
 }
\ No newline at end of file
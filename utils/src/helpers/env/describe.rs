@@ -0,0 +1,119 @@
+#[cfg(feature = "json")]
+use serde_json::{json, Map, Value};
+
+use super::FieldMeta;
+
+/// Everything `env_config!` knows about a variable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvVarInfo {
+    pub name: String,
+    pub ty: &'static str,
+    /// Evaluated default, `***` for secrets
+    pub default: Option<String>,
+    pub required: bool,
+    pub json_type: &'static str,
+    pub doc: String,
+    pub secret: bool,
+    pub rules: Vec<String>,
+}
+
+impl EnvVarInfo {
+    pub fn new(name: String, meta: &FieldMeta) -> Self {
+        Self {
+            name,
+            ty: meta.ty,
+            default: meta.default.clone(),
+            required: meta.default.is_none(),
+            json_type: meta.json_type,
+            doc: meta.doc.join(" "),
+            secret: meta.secret,
+            rules: meta.rules.clone(),
+        }
+    }
+}
+
+/// Variables of a config struct, nested sections are flattened
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigDescription {
+    pub vars: Vec<EnvVarInfo>,
+}
+
+impl ConfigDescription {
+    /// Template with a commented block per variable, optional ones are commented out
+    pub fn to_env_example(&self) -> String {
+        let mut out = String::new();
+        for var in self.vars.iter() {
+            if !var.doc.is_empty() {
+                out.push_str(&format!("# {}\n", var.doc));
+            }
+            let mut info = vec![var.ty.to_string()];
+            if let Some(default) = &var.default {
+                info.push(format!("default: {}", default));
+            }
+            if !var.rules.is_empty() {
+                info.push(var.rules.join(", "));
+            }
+            if var.secret {
+                info.push("secret".to_string());
+            }
+            out.push_str(&format!("# {}\n", info.join("; ")));
+            if var.required {
+                out.push_str(&format!("{}=\n\n", var.name));
+            } else {
+                out.push_str(&format!("# {}=\n\n", var.name));
+            }
+        }
+        out
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("| Variable | Type | Default | Required | Description |\n|---|---|---|---|---|\n");
+        for var in self.vars.iter() {
+            let mut doc = var.doc.clone();
+            if !var.rules.is_empty() {
+                doc = format!("{} ({})", doc, var.rules.join(", ")).trim().to_string();
+            }
+            out.push_str(&format!(
+                "| `{}` | `{}` | {} | {} | {} |\n",
+                var.name,
+                var.ty,
+                var.default.as_ref().map(|d| format!("`{}`", d)).unwrap_or_default(),
+                if var.required { "yes" } else { "no" },
+                doc.replace('|', "\\|"),
+            ));
+        }
+        out
+    }
+
+    /// JSON Schema of an object with a property per variable, typed by what the raw value parses as:
+    /// `boolean`, `integer`, `number`, or `string` for everything else
+    #[cfg(feature = "json")]
+    pub fn to_json_schema(&self) -> Value {
+        let mut properties = Map::new();
+        for var in self.vars.iter() {
+            let mut prop = Map::new();
+            prop.insert("type".into(), json!(var.json_type));
+            let mut description = var.doc.clone();
+            if let Some(default) = &var.default {
+                description = format!("{} Default: {}", description, default).trim().to_string();
+            }
+            if !var.rules.is_empty() {
+                description = format!("{} Rules: {}", description, var.rules.join(", ")).trim().to_string();
+            }
+            if !description.is_empty() {
+                prop.insert("description".into(), json!(description));
+            }
+            if var.secret {
+                prop.insert("writeOnly".into(), json!(true));
+            }
+            properties.insert(var.name.clone(), Value::Object(prop));
+        }
+        let required: Vec<&str> = self.vars.iter().filter(|v| v.required).map(|v| v.name.as_str()).collect();
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        })
+    }
+}
@@ -0,0 +1,347 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt::Debug;
+
+use super::{EnvConfigError, EnvParse, EnvVarInfo, Operator, Origin, ParseError, ValueSource, REDACTED};
+
+/// Where `env_config!` reads raw values from
+pub trait EnvSource {
+    fn get(&self, key: &str) -> Option<String>;
+    /// Value with the source it came from, layered sources report the exact layer
+    fn lookup(&self, key: &str) -> Option<(String, Origin)> {
+        self.get(key).map(|v| (v, Origin::Env))
+    }
+    /// Files which could not be read, reported together with invalid variables
+    fn errors(&self) -> Vec<ParseError> {
+        vec![]
+    }
+}
+
+pub struct ProcessEnv;
+
+impl EnvSource for ProcessEnv {
+    fn get(&self, key: &str) -> Option<String> {
+        std::env::var(key).ok()
+    }
+}
+
+type Check<T> = Box<dyn Fn(&T) -> Result<(), String> + Send + Sync>;
+/// Check of [`FieldMeta`], the value type is erased so metadata of every field fits one list
+type AnyCheck = Box<dyn Fn(&dyn Any) -> Result<(), String> + Send + Sync>;
+
+/// Field attribute, `#[name = "literal"]` and `#[name(args)]` in `env_config!`
+/// are turned into calls of the same named functions from [`attrs`]
+pub enum FieldAttr<T> {
+    Doc(&'static str),
+    Env(&'static str),
+    Secret,
+    /// Report a malformed value instead of falling back to the default
+    Strict,
+    /// Validation rule, `Err` explains what the value must be
+    Check(String, Check<T>),
+}
+
+impl<T> FieldAttr<T> {
+    pub fn check(rule: impl Into<String>, check: impl Fn(&T) -> Result<(), String> + Send + Sync + 'static) -> Self {
+        FieldAttr::Check(rule.into(), Box::new(check))
+    }
+}
+
+/// Collections which can be checked with `#[non_empty]`
+pub trait NonEmpty {
+    fn is_empty(&self) -> bool;
+}
+
+impl NonEmpty for String {
+    fn is_empty(&self) -> bool {
+        str::is_empty(self)
+    }
+}
+
+impl NonEmpty for std::path::PathBuf {
+    fn is_empty(&self) -> bool {
+        self.as_os_str().is_empty()
+    }
+}
+
+impl<T> NonEmpty for Vec<T> {
+    fn is_empty(&self) -> bool {
+        <[T]>::is_empty(self)
+    }
+}
+
+impl<K, V> NonEmpty for std::collections::HashMap<K, V> {
+    fn is_empty(&self) -> bool {
+        std::collections::HashMap::is_empty(self)
+    }
+}
+
+impl<T: NonEmpty + zeroize::Zeroize> NonEmpty for super::Secret<T> {
+    fn is_empty(&self) -> bool {
+        self.expose().is_empty()
+    }
+}
+
+pub mod attrs {
+    use std::fmt::Debug;
+    use std::ops::RangeBounds;
+
+    use super::{FieldAttr, NonEmpty};
+
+    /// Doc comments on fields
+    pub fn doc<T>(text: &'static str) -> FieldAttr<T> {
+        FieldAttr::Doc(text)
+    }
+
+    /// Read the field from `name` instead of the upper-cased field name
+    pub fn env<T>(name: &'static str) -> FieldAttr<T> {
+        FieldAttr::Env(name)
+    }
+
+    /// Hide the value in `Debug` and `Display` of the config
+    pub fn secret<T>() -> FieldAttr<T> {
+        FieldAttr::Secret
+    }
+
+    /// Fail on a malformed value even when the static falls back to defaults
+    pub fn strict<T>() -> FieldAttr<T> {
+        FieldAttr::Strict
+    }
+
+    /// `#[range(1..=65535)]`
+    pub fn range<T: PartialOrd + 'static, R: RangeBounds<T> + Debug + Send + Sync + 'static>(range: R) -> FieldAttr<T> {
+        let rule = format!("range({:?})", range);
+        let message = format!("must be in range {:?}", range);
+        FieldAttr::check(rule, move |v: &T| if range.contains(v) { Ok(()) } else { Err(message.clone()) })
+    }
+
+    /// `#[regex("^[a-z]+$")]`, the whole value must match. Compiled once with the field metadata
+    #[cfg(feature = "regex")]
+    pub fn regex<T: AsRef<str>>(pattern: &'static str) -> FieldAttr<T> {
+        let rule = format!("regex({:?})", pattern);
+        let regex = regex::Regex::new(&format!("^(?:{})$", pattern));
+        FieldAttr::check(rule, move |v: &T| match &regex {
+            Ok(regex) if regex.is_match(v.as_ref()) => Ok(()),
+            Ok(_) => Err(format!("must match {:?}", pattern)),
+            Err(e) => Err(format!("has invalid regex {:?} - {}", pattern, e)),
+        })
+    }
+
+    /// `#[one_of("debug", "info")]`
+    pub fn one_of<T: PartialEq<V>, V: Debug + Send + Sync + 'static>(values: Vec<V>) -> FieldAttr<T> {
+        let rule = format!("one_of({:?})", values);
+        let message = format!("must be one of {:?}", values);
+        FieldAttr::check(rule, move |v: &T| if values.iter().any(|allowed| v == allowed) { Ok(()) } else { Err(message.clone()) })
+    }
+
+    /// `#[non_empty]` for strings and collections
+    pub fn non_empty<T: NonEmpty>() -> FieldAttr<T> {
+        FieldAttr::check("non_empty", |v: &T| if v.is_empty() { Err("must not be empty".to_string()) } else { Ok(()) })
+    }
+}
+
+pub struct FieldMeta {
+    pub field: &'static str,
+    pub ty: &'static str,
+    pub env: Option<&'static str>,
+    pub doc: Vec<&'static str>,
+    pub secret: bool,
+    pub strict: bool,
+    /// Validation rules, e.g. `range(1..=65535)`
+    pub rules: Vec<String>,
+    /// Evaluated default as the config `Display` shows it, redacted for secrets
+    pub default: Option<String>,
+    /// JSON Schema type of the raw value, see [`EnvField::JSON_TYPE`]
+    pub json_type: &'static str,
+    checks: Vec<AnyCheck>,
+}
+
+impl FieldMeta {
+    pub fn new<T: EnvField + 'static>(field: &'static str, ty: &'static str, default: Option<T>, attrs: Vec<FieldAttr<T>>) -> Self {
+        let mut meta = Self {
+            field, ty, env: None, doc: vec![], secret: false, strict: false, rules: vec![], default: None,
+            json_type: T::JSON_TYPE, checks: vec![],
+        };
+        for attr in attrs {
+            match attr {
+                FieldAttr::Doc(text) => meta.doc.push(text.trim()),
+                FieldAttr::Env(name) => meta.env = Some(name),
+                FieldAttr::Secret => meta.secret = true,
+                FieldAttr::Strict => meta.strict = true,
+                FieldAttr::Check(rule, check) => {
+                    meta.rules.push(rule);
+                    meta.checks.push(Box::new(move |v| check(v.downcast_ref::<T>().expect("checked value of the field type"))));
+                }
+            }
+        }
+        meta.default = default.map(|v| {
+            let mut out = vec![];
+            v.dump_field("", meta.secret, &mut out);
+            match out.as_slice() {
+                [(key, value)] if key.is_empty() => value.clone(),
+                _ => out.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join(", "),
+            }
+        });
+        meta
+    }
+
+    /// Env name without prefix
+    pub fn env_name(&self) -> String {
+        match self.env {
+            Some(name) => name.to_string(),
+            None => self.field.to_ascii_uppercase(),
+        }
+    }
+
+    pub fn key(&self, prefix: &str) -> String {
+        format!("{}{}", prefix, self.env_name())
+    }
+}
+
+/// Anything which can be a field of `env_config!` struct: parsed values and nested sections
+pub trait EnvField: Sized {
+    /// Type of the property in [`ConfigDescription::to_json_schema`](super::ConfigDescription::to_json_schema)
+    const JSON_TYPE: &'static str = "string";
+    fn load_field(loader: &EnvLoader, key: &str, ty: &'static str) -> Result<Self, EnvConfigError>;
+    /// Flat `(key, value)` pairs for logging, values of secret fields are replaced
+    fn dump_field(&self, key: &str, secret: bool, out: &mut Vec<(String, String)>);
+    /// Metadata of the variables the field is read from
+    fn describe_field(key: &str, meta: &FieldMeta, out: &mut Vec<EnvVarInfo>) {
+        out.push(EnvVarInfo::new(key.to_string(), meta));
+    }
+}
+
+impl<T: EnvParse + Debug> EnvField for T {
+    const JSON_TYPE: &'static str = <T as EnvParse>::JSON_TYPE;
+
+    fn load_field(loader: &EnvLoader, key: &str, ty: &'static str) -> Result<Self, EnvConfigError> {
+        let Some(raw) = loader.raw(key)? else {
+            return Err(ParseError::Missing { name: key.to_string() }.into());
+        };
+        T::env_parse(&raw).ok_or_else(|| {
+            let raw = if T::SENSITIVE { REDACTED.to_string() } else { raw };
+            ParseError::Invalid { name: key.to_string(), ty, raw }.into()
+        })
+    }
+
+    fn dump_field(&self, key: &str, secret: bool, out: &mut Vec<(String, String)>) {
+        let value = if secret { REDACTED.to_string() } else { format!("{:?}", self) };
+        out.push((key.to_string(), value));
+    }
+}
+
+/// Reads fields one by one and keeps errors instead of failing on the first one
+pub struct EnvLoader<'a> {
+    source: &'a dyn EnvSource,
+    prefix: String,
+    report: &'a RefCell<Vec<ValueSource>>,
+    errors: Vec<ParseError>,
+    lenient: bool,
+}
+
+impl<'a> EnvLoader<'a> {
+    pub fn new(source: &'a dyn EnvSource, report: &'a RefCell<Vec<ValueSource>>) -> Self {
+        Self { source, prefix: String::new(), report, errors: source.errors(), lenient: false }
+    }
+
+    /// Malformed values of fields with a default fall back to it instead of being reported, except `#[strict]` ones
+    pub fn lenient(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+
+    /// Loader for a nested section, sharing the source and the report
+    pub fn nested(&self, prefix: String) -> EnvLoader<'a> {
+        EnvLoader { source: self.source, prefix, report: self.report, errors: vec![], lenient: self.lenient }
+    }
+
+    pub fn source(&self) -> &'a dyn EnvSource {
+        self.source
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn key(&self, meta: &FieldMeta) -> String {
+        meta.key(&self.prefix)
+    }
+
+    /// Value of `key`, or content of the file from `key_FILE` (docker and kubernetes secret mounts)
+    pub fn raw(&self, key: &str) -> Result<Option<String>, ParseError> {
+        if let Some((v, origin)) = self.source.lookup(key) {
+            self.record(key, origin);
+            return Ok(Some(v));
+        }
+        let Some((path, origin)) = self.source.lookup(&format!("{}_FILE", key)) else {
+            return Ok(None);
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(v) => {
+                self.record(key, origin);
+                Ok(Some(v.trim_end_matches(['\r', '\n']).to_string()))
+            }
+            Err(e) => Err(ParseError::File { name: key.to_string(), path, error: e.to_string() }),
+        }
+    }
+
+    pub fn read<T: EnvField>(&self, meta: &FieldMeta) -> Result<T, EnvConfigError> {
+        T::load_field(self, &self.key(meta), meta.ty).map_err(|mut e| {
+            if meta.secret {
+                e.redact();
+            }
+            e
+        })
+    }
+
+    /// Reads the field, falling back to `default` (`()` or `(value,)`) when it is missing,
+    /// and checks every validation rule of the field. A malformed value is an error unless the loader is lenient
+    pub fn field<T: EnvField + 'static, D: Operator<T, EnvConfigError>>(&mut self, meta: &FieldMeta, default: D) -> Option<T> {
+        let mut read = self.read::<T>(meta);
+        if self.lenient && default.has_default() && !meta.strict && matches!(&read, Err(e) if e.is_invalid()) {
+            let key = self.key(meta);
+            self.report.borrow_mut().retain(|v| v.key != key);
+            read = Err(ParseError::Missing { name: key }.into());
+        }
+        let missing = matches!(&read, Err(e) if e.is_missing());
+        let value = default.if_none(read);
+        if missing && value.is_ok() {
+            self.record(&self.key(meta), Origin::Default);
+        }
+        let value = self.check(value)?;
+        let name = self.key(meta);
+        let before = self.errors.len();
+        for check in meta.checks.iter() {
+            if let Err(rule) = check(&value) {
+                self.errors.push(ParseError::Rule { name: name.clone(), rule });
+            }
+        }
+        (self.errors.len() == before).then_some(value)
+    }
+
+
+    fn record(&self, key: &str, origin: Origin) {
+        self.report.borrow_mut().push(ValueSource { key: key.to_string(), origin });
+    }
+
+    pub fn check<T>(&mut self, value: Result<T, EnvConfigError>) -> Option<T> {
+        value.map_err(|e| self.errors.extend(e.errors)).ok()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    /// Reports that `check` of the whole section did not run because its fields failed to load
+    pub fn skip(&mut self, check: &'static str) {
+        self.errors.push(ParseError::Skipped { check });
+    }
+
+    pub fn finish(self) -> Result<(), EnvConfigError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(EnvConfigError { errors: self.errors })
+        }
+    }
+}
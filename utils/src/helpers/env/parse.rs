@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+#[cfg(feature = "humantime")]
+use std::time::Duration;
+
+#[cfg(feature = "url")]
+pub use url::Url;
+
+/// Conversion of a raw env value into a config field type
+pub trait EnvParse: Sized {
+    /// Raw value is not included into errors
+    const SENSITIVE: bool = false;
+    /// JSON Schema type the raw value conforms to, `boolean`, `integer`, `number` or `string`
+    const JSON_TYPE: &'static str = "string";
+    fn env_parse(raw: &str) -> Option<Self>;
+}
+
+/// Implements [`EnvParse`] through `FromStr` for the listed types
+/// ```
+/// #[derive(Debug)]
+/// struct Level(u8);
+/// impl std::str::FromStr for Level {
+///     type Err = ();
+///     fn from_str(s: &str) -> Result<Self, ()> { s.parse().map(Level).map_err(|_| ()) }
+/// }
+/// utils::env_parse_from_str!(Level);
+/// ```
+#[macro_export]
+macro_rules! env_parse_from_str {
+    ($($t:ty),* $(,)?) => {
+        $crate::env_parse_from_str!(#[json_type = "string"] $($t),*);
+    };
+    (#[json_type = $json:literal] $($t:ty),* $(,)?) => {
+        $(
+            impl $crate::helpers::env::EnvParse for $t {
+                const JSON_TYPE: &'static str = $json;
+                fn env_parse(raw: &str) -> Option<Self> {
+                    raw.parse::<$t>().ok()
+                }
+            }
+        )*
+    };
+}
+
+crate::env_parse_from_str!(#[json_type = "boolean"] bool);
+crate::env_parse_from_str!(#[json_type = "integer"] i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+crate::env_parse_from_str!(#[json_type = "number"] f32, f64);
+crate::env_parse_from_str!(
+    char, String,
+    std::path::PathBuf,
+    std::net::IpAddr, std::net::Ipv4Addr, std::net::Ipv6Addr,
+    std::net::SocketAddr,
+);
+#[cfg(feature = "url")]
+crate::env_parse_from_str!(Url);
+
+/// Comma separated list: `a, b, c`
+impl<T: EnvParse> EnvParse for Vec<T> {
+    fn env_parse(raw: &str) -> Option<Self> {
+        raw.split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(T::env_parse)
+            .collect()
+    }
+}
+
+/// Comma separated pairs: `key=value, other=value`
+impl<K: EnvParse + Eq + Hash, V: EnvParse> EnvParse for HashMap<K, V> {
+    fn env_parse(raw: &str) -> Option<Self> {
+        raw.split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(|pair| {
+                let (k, v) = pair.split_once('=')?;
+                Some((K::env_parse(k.trim())?, V::env_parse(v.trim())?))
+            })
+            .collect()
+    }
+}
+
+/// Human readable duration: `15m`, `1h 30m`, `500ms`
+#[cfg(feature = "humantime")]
+impl EnvParse for Duration {
+    fn env_parse(raw: &str) -> Option<Self> {
+        humantime::parse_duration(raw.trim()).ok()
+    }
+}
+
+crate::wrappers!(
+    /// Amount of bytes: `1024`, `10MB`, `512KiB`
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub ByteSize(pub u64)
+);
+
+impl EnvParse for ByteSize {
+    fn env_parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        let split = raw.find(|c: char| !c.is_ascii_digit()).unwrap_or(raw.len());
+        let (num, unit) = raw.split_at(split);
+        let num: u64 = num.parse().ok()?;
+        let mult: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+            "" | "b" => 1,
+            "kb" => 1_000,
+            "mb" => 1_000_000,
+            "gb" => 1_000_000_000,
+            "tb" => 1_000_000_000_000,
+            "kib" => 1 << 10,
+            "mib" => 1 << 20,
+            "gib" => 1 << 30,
+            "tib" => 1 << 40,
+            _ => return None,
+        };
+        num.checked_mul(mult).map(ByteSize)
+    }
+}
+
+/// Any `serde` type stored as JSON in a single variable
+#[cfg(feature = "json")]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Json<T>(pub T);
+
+#[cfg(feature = "json")]
+impl<T> std::ops::Deref for Json<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> std::ops::DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T: serde::de::DeserializeOwned> EnvParse for Json<T> {
+    fn env_parse(raw: &str) -> Option<Self> {
+        serde_json::from_str(raw).ok().map(Json)
+    }
+}
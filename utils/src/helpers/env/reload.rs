@@ -0,0 +1,114 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use arc_swap::ArcSwap;
+use notify::{RecursiveMode, Watcher};
+
+use super::EnvConfigError;
+
+type Listener<T> = Arc<dyn Fn(&T, &T) + Send + Sync>;
+
+/// Config which can be re-read at runtime, readers get a consistent snapshot through [`Reloadable::get`]
+pub struct Reloadable<T> {
+    current: ArcSwap<T>,
+    load: Box<dyn Fn() -> Result<T, EnvConfigError> + Send + Sync>,
+    listeners: Mutex<Vec<Listener<T>>>,
+    last_error: Mutex<Option<EnvConfigError>>,
+    /// Held for the whole reload, so concurrent reloads store their values in the order they were read
+    reloading: Mutex<()>,
+}
+
+impl<T: Send + Sync + 'static> Reloadable<T> {
+    pub fn new(load: impl Fn() -> Result<T, EnvConfigError> + Send + Sync + 'static) -> Result<Self, EnvConfigError> {
+        let current = load()?;
+        Ok(Self {
+            current: ArcSwap::from_pointee(current),
+            load: Box::new(load),
+            listeners: Mutex::new(vec![]),
+            last_error: Mutex::new(None),
+            reloading: Mutex::new(()),
+        })
+    }
+
+    pub fn get(&self) -> Arc<T> {
+        self.current.load_full()
+    }
+
+    /// Re-reads the sources and swaps the value, on error the current value is kept.
+    /// Listeners are called without any lock held, they may subscribe or reload themselves
+    pub fn reload(&self) -> Result<Arc<T>, EnvConfigError> {
+        let (old, new) = {
+            let _reloading = self.reloading.lock().unwrap_or_else(PoisonError::into_inner);
+            let new = match (self.load)() {
+                Ok(v) => Arc::new(v),
+                Err(e) => {
+                    *self.last_error.lock().unwrap_or_else(PoisonError::into_inner) = Some(e.clone());
+                    return Err(e);
+                }
+            };
+            *self.last_error.lock().unwrap_or_else(PoisonError::into_inner) = None;
+            (self.current.swap(new.clone()), new)
+        };
+        let listeners = self.listeners.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        for listener in listeners {
+            listener(&old, &new);
+        }
+        Ok(new)
+    }
+
+    /// Called with the old and the new value after every successful reload
+    pub fn on_change(&self, f: impl Fn(&T, &T) + Send + Sync + 'static) {
+        self.listeners.lock().unwrap_or_else(PoisonError::into_inner).push(Arc::new(f));
+    }
+
+    /// Error of the last failed reload, cleared by a successful one
+    pub fn last_error(&self) -> Option<EnvConfigError> {
+        self.last_error.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// Reloads on every change of the files, watching stops when [`ConfigWatcher`] is dropped.
+    /// Parent directories are watched, so files may be created later or replaced by editors
+    pub fn watch(self: &Arc<Self>, paths: &[PathBuf]) -> notify::Result<ConfigWatcher> {
+        let this = self.clone();
+        watch_files(paths, move || { let _ = this.reload(); })
+    }
+
+    /// Same as [`Reloadable::watch`] for values living in statics
+    pub fn watch_static(&'static self, paths: &[PathBuf]) -> notify::Result<ConfigWatcher> {
+        watch_files(paths, move || { let _ = self.reload(); })
+    }
+}
+
+/// Keeps the file watcher alive
+pub struct ConfigWatcher {
+    _watcher: notify::RecommendedWatcher,
+}
+
+fn watch_files(paths: &[PathBuf], on_change: impl Fn() + Send + 'static) -> notify::Result<ConfigWatcher> {
+    let files: Vec<PathBuf> = paths
+        .iter()
+        .filter(|p| !p.as_os_str().is_empty())
+        .map(|p| std::path::absolute(p).unwrap_or_else(|_| p.clone()))
+        .collect();
+    let watched = files.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        if event.kind.is_access() {
+            return;
+        }
+        if event.paths.iter().any(|p| watched.iter().any(|f| same_file(p, f))) {
+            on_change();
+        }
+    })?;
+    let mut dirs: Vec<&Path> = files.iter().filter_map(|f| f.parent()).collect();
+    dirs.sort();
+    dirs.dedup();
+    for dir in dirs {
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    }
+    Ok(ConfigWatcher { _watcher: watcher })
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    a == b || (a.file_name() == b.file_name() && a.parent().and_then(|p| p.canonicalize().ok()) == b.parent().and_then(|p| p.canonicalize().ok()))
+}
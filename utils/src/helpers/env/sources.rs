@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::{EnvSource, ParseError, ProcessEnv};
+
+/// Where a config value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    /// Default value from `env_config!`
+    Default,
+    /// YAML, TOML or JSON config file
+    File(String),
+    /// `.env` file
+    DotEnv(String),
+    /// Process environment
+    Env,
+    /// Command line arguments
+    Cli,
+    /// In-memory map
+    Map,
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Default => f.write_str("default"),
+            Self::File(path) => write!(f, "file {}", path),
+            Self::DotEnv(path) => write!(f, "dotenv {}", path),
+            Self::Env => f.write_str("env"),
+            Self::Cli => f.write_str("cli"),
+            Self::Map => f.write_str("map"),
+        }
+    }
+}
+
+/// Variable and the source it was read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueSource {
+    pub key: String,
+    pub origin: Origin,
+}
+
+impl std::fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} <- {}", self.key, self.origin)
+    }
+}
+
+/// Fixed set of values, used for files, `.env` and cli arguments
+pub struct MapSource {
+    origin: Origin,
+    values: HashMap<String, String>,
+}
+
+impl MapSource {
+    pub fn new(origin: Origin, values: HashMap<String, String>) -> Self {
+        Self { origin, values }
+    }
+}
+
+/// In-memory values, e.g. `[("PORT", "8080")]` in tests
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for MapSource {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self::new(Origin::Map, iter.into_iter().map(|(k, v)| (k.into(), v.into())).collect())
+    }
+}
+
+impl EnvSource for MapSource {
+    fn get(&self, key: &str) -> Option<String> {
+        self.values.get(key).cloned()
+    }
+
+    fn lookup(&self, key: &str) -> Option<(String, Origin)> {
+        self.get(key).map(|v| (v, self.origin.clone()))
+    }
+}
+
+/// Layered sources, every next layer overrides the previous ones.
+/// The usual order is defaults < config file < `.env` < process env < cli:
+/// ```
+/// use utils::helpers::env::EnvSources;
+/// let sources = EnvSources::new()
+///     .file("config.yaml")
+///     .dotenv_if(cfg!(debug_assertions), ".env")
+///     .env()
+///     .cli_args();
+/// ```
+#[derive(Default)]
+pub struct EnvSources {
+    layers: Vec<Box<dyn EnvSource + Send + Sync>>,
+    paths: Vec<PathBuf>,
+    errors: Vec<ParseError>,
+}
+
+impl EnvSources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn source(mut self, source: impl EnvSource + Send + Sync + 'static) -> Self {
+        self.layers.push(Box::new(source));
+        self
+    }
+
+    /// YAML, TOML or JSON file picked by extension, nested keys are joined with `__`:
+    /// `db: { url: .. }` is read as `DB__URL`. Missing file is skipped. Needs the `files` feature
+    #[cfg(feature = "files")]
+    pub fn file(mut self, path: &str) -> Self {
+        self.paths.push(PathBuf::from(path));
+        match read_config_file(path) {
+            Ok(Some(values)) => self.source(MapSource::new(Origin::File(path.to_string()), values)),
+            Ok(None) => self,
+            Err(error) => {
+                self.errors.push(ParseError::Source { path: path.to_string(), error });
+                self
+            }
+        }
+    }
+
+    /// `.env` file, unlike `dotenvy::dotenv` it does not modify the process env.
+    /// Missing file is skipped
+    pub fn dotenv(mut self, path: &str) -> Self {
+        self.paths.push(PathBuf::from(path));
+        let iter = match dotenvy::from_filename_iter(path) {
+            Ok(iter) => iter,
+            Err(e) if e.not_found() => return self,
+            Err(e) => {
+                self.errors.push(ParseError::Source { path: path.to_string(), error: e.to_string() });
+                return self;
+            }
+        };
+        let mut values = HashMap::new();
+        for item in iter {
+            match item {
+                Ok((k, v)) => { values.insert(k, v); }
+                Err(e) => self.errors.push(ParseError::Source { path: path.to_string(), error: e.to_string() }),
+            }
+        }
+        self.source(MapSource::new(Origin::DotEnv(path.to_string()), values))
+    }
+
+    /// `.env` file only when `enabled`, e.g. `cfg!(debug_assertions)` to skip it in production
+    pub fn dotenv_if(self, enabled: bool, path: &str) -> Self {
+        if enabled { self.dotenv(path) } else { self }
+    }
+
+    pub fn env(self) -> Self {
+        self.source(ProcessEnv)
+    }
+
+    /// `--db-url=..`, `--db-url ..` and `--debug` are read as `DB_URL` and `DEBUG=true`,
+    /// other arguments are ignored
+    pub fn cli(self, args: impl IntoIterator<Item = String>) -> Self {
+        self.source(MapSource::new(Origin::Cli, parse_args(args)))
+    }
+
+    /// Arguments of the current process
+    pub fn cli_args(self) -> Self {
+        self.cli(std::env::args().skip(1))
+    }
+
+    /// Config and `.env` files, including missing ones
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+}
+
+impl EnvSource for EnvSources {
+    fn get(&self, key: &str) -> Option<String> {
+        self.lookup(key).map(|(v, _)| v)
+    }
+
+    fn lookup(&self, key: &str) -> Option<(String, Origin)> {
+        self.layers.iter().rev().find_map(|l| l.lookup(key))
+    }
+
+    fn errors(&self) -> Vec<ParseError> {
+        self.errors.clone()
+    }
+}
+
+/// What `env_config!` accepts before `=>`: a `.env` filename or [`EnvSources`]
+pub trait IntoEnvSources {
+    fn into_sources(self) -> EnvSources;
+}
+
+impl IntoEnvSources for EnvSources {
+    fn into_sources(self) -> EnvSources {
+        self
+    }
+}
+
+/// `.env` file overridden by the process env
+impl IntoEnvSources for &str {
+    fn into_sources(self) -> EnvSources {
+        EnvSources::new().dotenv(self).env()
+    }
+}
+
+impl IntoEnvSources for String {
+    fn into_sources(self) -> EnvSources {
+        self.as_str().into_sources()
+    }
+}
+
+fn env_key(raw: &str) -> String {
+    raw.trim().replace('-', "_").to_ascii_uppercase()
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    let mut args = args.into_iter().peekable();
+    while let Some(arg) = args.next() {
+        let Some(arg) = arg.strip_prefix("--") else { continue };
+        let (key, value) = match arg.split_once('=') {
+            Some((k, v)) => (k, v.to_string()),
+            None => match args.next_if(|next| !next.starts_with("--")) {
+                Some(v) => (arg, v),
+                None => (arg, "true".to_string()),
+            },
+        };
+        values.insert(env_key(key), value);
+    }
+    values
+}
+
+#[cfg(feature = "files")]
+fn read_config_file(path: &str) -> Result<Option<HashMap<String, String>>, String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    let ext = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("");
+    let value: serde_json::Value = match ext {
+        "yaml" | "yml" => serde_yaml::from_str(&content).map_err(|e| e.to_string())?,
+        "toml" => toml::from_str(&content).map_err(|e| e.to_string())?,
+        "json" => serde_json::from_str(&content).map_err(|e| e.to_string())?,
+        _ => return Err(format!("unsupported config format: {:?}", ext)),
+    };
+    let mut values = HashMap::new();
+    flatten("", &value, &mut values);
+    Ok(Some(values))
+}
+
+#[cfg(feature = "files")]
+fn flatten(key: &str, value: &serde_json::Value, out: &mut HashMap<String, String>) {
+    use serde_json::Value;
+    match value {
+        Value::Null => {}
+        Value::Object(map) => {
+            for (k, v) in map {
+                let k = if key.is_empty() { env_key(k) } else { format!("{}__{}", key, env_key(k)) };
+                flatten(&k, v, out);
+            }
+        }
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(scalar).collect();
+            out.insert(key.to_string(), items.join(","));
+        }
+        v => {
+            out.insert(key.to_string(), scalar(v));
+        }
+    }
+}
+
+#[cfg(feature = "files")]
+fn scalar(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        v => v.to_string(),
+    }
+}
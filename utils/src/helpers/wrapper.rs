@@ -1,37 +1,288 @@
+#[cfg(feature = "serde")]
+pub use serde;
+
+/// Value rejected by the check of a validated wrapper, or inner value which failed to parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrapperError {
+    pub ty: &'static str,
+    pub reason: String,
+}
+
+impl std::fmt::Display for WrapperError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid {} - {}", self.ty, self.reason)
+    }
+}
+
+impl std::error::Error for WrapperError {}
+
+/// ## Newtypes over a single value
+/// Every wrapper gets `Deref`, `DerefMut`, `From<inner>` and `into_inner()`.
+/// Options in brackets after the inner type add transparent impls forwarded to the inner value:
+/// - `serde` - `Serialize` and `Deserialize`
+/// - `display` and `from_str` - `Display` and `FromStr`
+/// - `sqlx` - `sqlx::Type`, `Encode` and `Decode` for any database, the crate must depend on `sqlx`
+/// - `as_ref` and `borrow` - `AsRef<inner>` and `Borrow<inner>`
+/// - `check(predicate)` - validated mode: instead of `From` and `DerefMut` there are `try_new` and `TryFrom`,
+///   `Deserialize`, `FromStr` and `Decode` reject values failing the predicate.
+///   The inner value must be private, otherwise it could be built or changed without the check
+/// ```
+/// utils::wrappers!(
+///     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///     pub UserId(pub u64) [serde, display, from_str]
+///
+///     #[derive(Debug, Clone, PartialEq, Eq)]
+///     pub Email(String) [serde, display, as_ref, check(|v: &String| v.contains('@'))]
+/// );
+/// assert_eq!("7".parse::<UserId>().unwrap(), UserId(7));
+/// assert!(Email::try_new("nobody".to_string()).is_err());
+/// ```
+/// ```compile_fail
+/// utils::wrappers!(
+///     pub Email(pub String) [check(|v: &String| v.contains('@'))]
+/// );
+/// ```
 #[macro_export]
 macro_rules! wrappers {
-    (
-        $(
-            $(#[$struct_meta:meta])*
-            $wrapper_vis:vis $name:ident($inner_vis:vis $inner_ty:ty)
-        )*
-    ) => {
-        $(
-            $(#[$struct_meta])*
-            $wrapper_vis struct $name (
-                $inner_vis $inner_ty
-            );
-
-            impl std::ops::Deref for $name {
-                type Target = $inner_ty;
-                fn deref(&self) -> &Self::Target {
-                    &self.0
-                }
+    (@wrapper [$(#[$struct_meta:meta])*] $wrapper_vis:vis $name:ident($inner_vis:vis $inner_ty:ty) [$($opts:tt)*]) => {
+        $(#[$struct_meta])*
+        $wrapper_vis struct $name (
+            $inner_vis $inner_ty
+        );
+
+        impl std::ops::Deref for $name {
+            type Target = $inner_ty;
+            fn deref(&self) -> &Self::Target {
+                &self.0
             }
+        }
 
-            impl std::ops::DerefMut for $name {
-                fn deref_mut(&mut self) -> &mut Self::Target {
-                    &mut self.0
-                }
+        impl $name {
+            #[allow(dead_code)]
+            pub fn into_inner(self) -> $inner_ty {
+                self.0
+            }
+        }
+
+        $crate::wrappers!(@ctor $name $inner_ty [$($opts)*]);
+        $crate::wrappers!(@opts $name $inner_ty [$($opts)*]);
+    };
+    (@ctor $name:ident $inner_ty:ty []) => {
+        impl std::ops::DerefMut for $name {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
             }
+        }
 
-            impl From<$inner_ty> for $name {
-                fn from(value: $inner_ty) -> Self {
-                    Self ( value )
+        impl From<$inner_ty> for $name {
+            fn from(value: $inner_ty) -> Self {
+                Self ( value )
+            }
+        }
+
+        impl $name {
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            fn __wrapper_checked(value: $inner_ty) -> Result<Self, $crate::helpers::wrapper::WrapperError> {
+                Ok(Self(value))
+            }
+        }
+    };
+    (@ctor $name:ident $inner_ty:ty [check($check:expr) $(, $($rest:tt)*)?]) => {
+        impl $name {
+            /// Fails if the value does not pass the check of the wrapper
+            pub fn try_new(value: $inner_ty) -> Result<Self, $crate::helpers::wrapper::WrapperError> {
+                let check: fn(&$inner_ty) -> bool = $check;
+                if check(&value) {
+                    Ok(Self(value))
+                } else {
+                    Err($crate::helpers::wrapper::WrapperError { ty: stringify!($name), reason: "validation failed".to_string() })
                 }
             }
-        )*
+
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            fn __wrapper_checked(value: $inner_ty) -> Result<Self, $crate::helpers::wrapper::WrapperError> {
+                Self::try_new(value)
+            }
+        }
+
+        impl TryFrom<$inner_ty> for $name {
+            type Error = $crate::helpers::wrapper::WrapperError;
+            fn try_from(value: $inner_ty) -> Result<Self, Self::Error> {
+                Self::try_new(value)
+            }
+        }
+    };
+    (@ctor $name:ident $inner_ty:ty [$opt:ident $(($($arg:tt)*))? $(, $($rest:tt)*)?]) => {
+        $crate::wrappers!(@ctor $name $inner_ty [$($($rest)*)?]);
+    };
+    (@private $name:ident []) => {};
+    (@private $name:ident [check $(($($arg:tt)*))? $(, $($rest:tt)*)?]) => {
+        compile_error!(concat!("inner value of validated wrapper ", stringify!($name), " must be private"));
+    };
+    (@private $name:ident [$opt:ident $(($($arg:tt)*))? $(, $($rest:tt)*)?]) => {
+        $crate::wrappers!(@private $name [$($($rest)*)?]);
+    };
+    (@opts $name:ident $inner_ty:ty []) => {};
+    (@opts $name:ident $inner_ty:ty [$opt:ident $(($($arg:tt)*))? $(, $($rest:tt)*)?]) => {
+        $crate::wrappers!(@opt $opt $name $inner_ty);
+        $crate::wrappers!(@opts $name $inner_ty [$($($rest)*)?]);
+    };
+    (@opt check $name:ident $inner_ty:ty) => {};
+    (@opt serde $name:ident $inner_ty:ty) => {
+        impl $crate::helpers::wrapper::serde::Serialize for $name {
+            fn serialize<S: $crate::helpers::wrapper::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                $crate::helpers::wrapper::serde::Serialize::serialize(&self.0, serializer)
+            }
+        }
+
+        impl<'de> $crate::helpers::wrapper::serde::Deserialize<'de> for $name {
+            fn deserialize<D: $crate::helpers::wrapper::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = <$inner_ty as $crate::helpers::wrapper::serde::Deserialize<'de>>::deserialize(deserializer)?;
+                Self::__wrapper_checked(value).map_err(<D::Error as $crate::helpers::wrapper::serde::de::Error>::custom)
+            }
+        }
+    };
+    (@opt display $name:ident $inner_ty:ty) => {
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Display::fmt(&self.0, f)
+            }
+        }
     };
+    (@opt from_str $name:ident $inner_ty:ty) => {
+        impl std::str::FromStr for $name {
+            type Err = $crate::helpers::wrapper::WrapperError;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let value = s.parse::<$inner_ty>().map_err(|e| $crate::helpers::wrapper::WrapperError { ty: stringify!($name), reason: e.to_string() })?;
+                Self::__wrapper_checked(value)
+            }
+        }
+    };
+    (@opt sqlx $name:ident $inner_ty:ty) => {
+        impl<DB: ::sqlx::Database> ::sqlx::Type<DB> for $name where $inner_ty: ::sqlx::Type<DB> {
+            fn type_info() -> DB::TypeInfo {
+                <$inner_ty as ::sqlx::Type<DB>>::type_info()
+            }
+            fn compatible(ty: &DB::TypeInfo) -> bool {
+                <$inner_ty as ::sqlx::Type<DB>>::compatible(ty)
+            }
+        }
+
+        impl<'q, DB: ::sqlx::Database> ::sqlx::Encode<'q, DB> for $name where $inner_ty: ::sqlx::Encode<'q, DB> {
+            fn encode_by_ref(&self, buf: &mut <DB as ::sqlx::Database>::ArgumentBuffer<'q>) -> Result<::sqlx::encode::IsNull, ::sqlx::error::BoxDynError> {
+                self.0.encode_by_ref(buf)
+            }
+            fn produces(&self) -> Option<DB::TypeInfo> {
+                self.0.produces()
+            }
+            fn size_hint(&self) -> usize {
+                self.0.size_hint()
+            }
+        }
+
+        impl<'r, DB: ::sqlx::Database> ::sqlx::Decode<'r, DB> for $name where $inner_ty: ::sqlx::Decode<'r, DB> {
+            fn decode(value: <DB as ::sqlx::Database>::ValueRef<'r>) -> Result<Self, ::sqlx::error::BoxDynError> {
+                let value = <$inner_ty as ::sqlx::Decode<'r, DB>>::decode(value)?;
+                Ok(Self::__wrapper_checked(value)?)
+            }
+        }
+    };
+    (@opt as_ref $name:ident $inner_ty:ty) => {
+        impl AsRef<$inner_ty> for $name {
+            fn as_ref(&self) -> &$inner_ty {
+                &self.0
+            }
+        }
+    };
+    (@opt borrow $name:ident $inner_ty:ty) => {
+        impl std::borrow::Borrow<$inner_ty> for $name {
+            fn borrow(&self) -> &$inner_ty {
+                &self.0
+            }
+        }
+    };
+    () => {};
+    (
+        $(#[$struct_meta:meta])*
+        $wrapper_vis:vis $name:ident(pub $($inner:tt)*) [$($opts:tt)*]
+        $($rest:tt)*
+    ) => {
+        $crate::wrappers!(@private $name [$($opts)*]);
+        $crate::wrappers!(@wrapper [$(#[$struct_meta])*] $wrapper_vis $name(pub $($inner)*) [$($opts)*]);
+        $crate::wrappers!($($rest)*);
+    };
+    (
+        $(#[$struct_meta:meta])*
+        $wrapper_vis:vis $name:ident($inner_vis:vis $inner_ty:ty) [$($opts:tt)*]
+        $($rest:tt)*
+    ) => {
+        $crate::wrappers!(@wrapper [$(#[$struct_meta])*] $wrapper_vis $name($inner_vis $inner_ty) [$($opts)*]);
+        $crate::wrappers!($($rest)*);
+    };
+    (
+        $(#[$struct_meta:meta])*
+        $wrapper_vis:vis $name:ident($inner_vis:vis $inner_ty:ty)
+        $($rest:tt)*
+    ) => {
+        $crate::wrappers!(@wrapper [$(#[$struct_meta])*] $wrapper_vis $name($inner_vis $inner_ty) []);
+        $crate::wrappers!($($rest)*);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    crate::wrappers!(
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub UserId(pub i64) [serde, display, from_str, sqlx, as_ref, borrow]
+
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub Email(String) [serde, display, from_str, sqlx, check(|v: &String| !v.is_empty() && v.contains('@'))]
+
+        #[derive(Debug)]
+        pub Plain(pub u8)
+    );
+
+    #[test]
+    fn test_transparent_impls() {
+        let id = UserId::from(5);
+        assert_eq!(serde_json::to_string(&id).unwrap(), "5");
+        assert_eq!(serde_json::from_str::<UserId>("7").unwrap(), UserId(7));
+        assert_eq!(id.to_string(), "5");
+        assert_eq!("9".parse::<UserId>().unwrap(), UserId(9));
+        assert!("x".parse::<UserId>().unwrap_err().to_string().starts_with("Invalid UserId - "));
+        assert_eq!(*id.as_ref(), 5);
+        let ids: HashSet<UserId> = [id].into();
+        assert!(ids.contains(&5));
+
+        let mut plain = Plain::from(1);
+        *plain += 1;
+        assert_eq!(plain.into_inner(), 2);
+    }
 
+    #[test]
+    fn test_validated() {
+        assert!(Email::try_new(String::new()).is_err());
+        let email = Email::try_new("a@b.c".to_string()).unwrap();
+        assert_eq!(email.len(), 5);
+        assert_eq!(Email::try_from("x".to_string()).unwrap_err().to_string(), "Invalid Email - validation failed");
+        assert!("nobody".parse::<Email>().is_err());
+        assert!(serde_json::from_str::<Email>("\"nobody\"").is_err());
+        assert_eq!(serde_json::from_str::<Email>("\"a@b.c\"").unwrap(), email);
+    }
 
-}
\ No newline at end of file
+    #[tokio::test]
+    async fn test_sqlx() {
+        use sqlx::Connection;
+        let mut conn = sqlx::SqliteConnection::connect("sqlite::memory:").await.unwrap();
+        let id: UserId = sqlx::query_scalar("SELECT ?").bind(UserId(42)).fetch_one(&mut conn).await.unwrap();
+        assert_eq!(id, UserId(42));
+        let email: Email = sqlx::query_scalar("SELECT 'a@b.c'").fetch_one(&mut conn).await.unwrap();
+        assert_eq!(email.as_str(), "a@b.c");
+        let invalid = sqlx::query_scalar::<_, Email>("SELECT 'nobody'").fetch_one(&mut conn).await;
+        assert!(invalid.is_err());
+    }
+}